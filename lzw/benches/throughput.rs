@@ -0,0 +1,150 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use salzweg::{
+    decoder::{DecodingOptions, GifStyleDecoder, TiffStyleDecoder, VariableDecoderState},
+    encoder::{FixedEncoder, GifStyleEncoder, TiffStyleEncoder},
+    io::{BigEndianReader, BitReader, LittleEndianReader},
+    Endianness,
+};
+use std::{fs::File, io::Read, path::Path};
+
+/// Forwards one byte per call to `read`, which is how the encoders used to consume their
+/// input, through `Read::bytes()`. On an unbuffered file, that's a syscall per byte.
+struct ByteByByte<R: Read>(R);
+
+impl<R: Read> Read for ByteByByte<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(1);
+        self.0.read(&mut buf[..len])
+    }
+}
+
+fn open_file() -> File {
+    File::open(Path::new(env!("CARGO_MANIFEST_DIR")).join("../test-assets/lorem_ipsum.txt"))
+        .unwrap()
+}
+
+fn bench_encoding(c: &mut Criterion) {
+    let len = open_file().metadata().unwrap().len();
+    let mut group = c.benchmark_group("Encode from unbuffered file");
+    group.throughput(Throughput::Bytes(len));
+
+    group.bench_function(BenchmarkId::new("GIF", "Bulk reads"), |b| {
+        b.iter(|| GifStyleEncoder::encode_to_vec(black_box(open_file()), 7).unwrap())
+    });
+    group.bench_function(BenchmarkId::new("GIF", "Byte by byte reads"), |b| {
+        b.iter(|| GifStyleEncoder::encode_to_vec(ByteByByte(black_box(open_file())), 7).unwrap())
+    });
+    group.bench_function(BenchmarkId::new("Fixed", "Bulk reads"), |b| {
+        b.iter(|| {
+            FixedEncoder::encode_to_vec(black_box(open_file()), Endianness::LittleEndian).unwrap()
+        })
+    });
+    group.bench_function(BenchmarkId::new("Fixed", "Byte by byte reads"), |b| {
+        b.iter(|| {
+            FixedEncoder::encode_to_vec(
+                ByteByByte(black_box(open_file())),
+                Endianness::LittleEndian,
+            )
+            .unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_decoding(c: &mut Criterion) {
+    let mut data = vec![];
+    open_file().read_to_end(&mut data).unwrap();
+    let mut group = c.benchmark_group("Decode");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+
+    let gif = GifStyleEncoder::encode_to_vec(&data[..], 7).unwrap();
+    group.bench_with_input(BenchmarkId::new("GIF", "Slice"), &gif[..], |b, i| {
+        b.iter(|| GifStyleDecoder::decode_to_vec(black_box(i), 7).unwrap())
+    });
+    let tiff = TiffStyleEncoder::encode_to_vec(&data[..]).unwrap();
+    group.bench_with_input(BenchmarkId::new("TIFF", "Slice"), &tiff[..], |b, i| {
+        b.iter(|| TiffStyleDecoder::decode_to_vec(black_box(i)).unwrap())
+    });
+
+    group.finish();
+}
+
+fn bench_bit_reading(c: &mut Criterion) {
+    let mut data = vec![];
+    open_file().read_to_end(&mut data).unwrap();
+    let mut group = c.benchmark_group("Read 12 bit codes");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+
+    group.bench_with_input("Little endian", &data[..], |b, i| {
+        b.iter(|| {
+            let mut reader = LittleEndianReader::new(black_box(i));
+            let mut sum = 0u32;
+            while let Ok(code) = reader.read_one(12) {
+                sum = sum.wrapping_add(code as u32);
+            }
+            sum
+        })
+    });
+    group.bench_with_input("Big endian", &data[..], |b, i| {
+        b.iter(|| {
+            let mut reader = BigEndianReader::new(black_box(i));
+            let mut sum = 0u32;
+            while let Ok(code) = reader.read_one(12) {
+                sum = sum.wrapping_add(code as u32);
+            }
+            sum
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_frames(c: &mut Criterion) {
+    let mut data = vec![];
+    open_file().read_to_end(&mut data).unwrap();
+    // Many tiny frames, as in an animated GIF where only small areas change.
+    let frames: Vec<Vec<u8>> = data
+        .chunks(64)
+        .map(|frame| GifStyleEncoder::encode_to_vec(frame, 7).unwrap())
+        .collect();
+    let mut group = c.benchmark_group("Decode frames");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+
+    group.bench_with_input("One shot", &frames, |b, frames| {
+        let mut output = Vec::with_capacity(data.len());
+        b.iter(|| {
+            output.clear();
+            for frame in frames {
+                GifStyleDecoder::decode(black_box(&frame[..]), &mut output, 7).unwrap();
+            }
+        })
+    });
+    group.bench_with_input("Reusing state", &frames, |b, frames| {
+        let mut output = Vec::with_capacity(data.len());
+        let mut state = VariableDecoderState::new();
+        b.iter(|| {
+            output.clear();
+            for frame in frames {
+                let options = DecodingOptions {
+                    code_size: 7,
+                    ..Default::default()
+                };
+                state
+                    .decode_reusing(black_box(&frame[..]), &mut output, options)
+                    .unwrap();
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_encoding,
+    bench_decoding,
+    bench_bit_reading,
+    bench_frames
+);
+criterion_main!(benches);
@@ -10,6 +10,7 @@ fn main() -> Result<()> {
         LOREM_IPSUM,
         &mut compressed,
         7,
+        12,
         salzweg::Endianness::LittleEndian,
         CodeSizeStrategy::Default,
     )?;
@@ -22,6 +23,7 @@ fn main() -> Result<()> {
         &compressed[..],
         &mut decompressed,
         7,
+        12,
         salzweg::Endianness::LittleEndian,
         CodeSizeStrategy::Default,
     )?;
@@ -0,0 +1,178 @@
+//! Stand-ins for the few `std::io` items the crate relies on, for builds without `std`.
+//!
+//! They mirror the `std::io` signatures, so the rest of the crate doesn't need to know which
+//! flavor it is using. They are re-exported by the [crate::io] module.
+use alloc::{string::String, vec::Vec};
+use core::fmt::{Debug, Display};
+
+/// The categories of [Error], a subset of the `std::io` ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A parameter was incorrect.
+    InvalidInput,
+    /// The data read was not valid.
+    InvalidData,
+    /// The data ended before what was expected was read.
+    UnexpectedEof,
+    /// Nothing could be written, usually as the output is full.
+    WriteZero,
+    /// The operation was interrupted, and can be retried.
+    Interrupted,
+    /// Any other error.
+    Other,
+}
+
+/// An I/O error, made of its [ErrorKind] and a message.
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    message: String,
+}
+
+impl Error {
+    /// Create an error of the given kind.
+    pub fn new<M: Into<String>>(kind: ErrorKind, message: M) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// Create an error of the [ErrorKind::Other] kind.
+    pub fn other<M: Into<String>>(message: M) -> Self {
+        Self::new(ErrorKind::Other, message)
+    }
+
+    /// The kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Self {
+            kind,
+            message: String::new(),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.message.is_empty() {
+            Debug::fmt(&self.kind, f)
+        } else {
+            f.write_str(&self.message)
+        }
+    }
+}
+
+/// The source of bytes, like `std::io::Read`.
+pub trait Read {
+    /// Pull some bytes into `buf`, returning how many were read: 0 once the data ended.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error>;
+
+    /// Read exactly enough bytes to fill `buf`.
+    ///
+    /// Fails with [ErrorKind::UnexpectedEof] if the data ends before.
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        let mut buf = buf;
+        while !buf.is_empty() {
+            match self.read(buf) {
+                Ok(0) => {
+                    return Err(Error::new(
+                        ErrorKind::UnexpectedEof,
+                        "failed to fill whole buffer",
+                    ))
+                }
+                Ok(read) => buf = &mut buf[read..],
+                Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The destination of bytes, like `std::io::Write`.
+pub trait Write {
+    /// Write some of the bytes of `buf`, returning how many were written.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error>;
+
+    /// Make sure all the bytes written so far reach their destination.
+    fn flush(&mut self) -> Result<(), Error>;
+
+    /// Write all the bytes of `buf`.
+    ///
+    /// Fails with [ErrorKind::WriteZero] if the output stops accepting bytes before.
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+        let mut buf = buf;
+        while !buf.is_empty() {
+            match self.write(buf) {
+                Ok(0) => {
+                    return Err(Error::new(
+                        ErrorKind::WriteZero,
+                        "failed to write whole buffer",
+                    ))
+                }
+                Ok(written) => buf = &buf[written..],
+                Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Read for &[u8] {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let amount = buf.len().min(self.len());
+        let (read, rest) = self.split_at(amount);
+        buf[..amount].copy_from_slice(read);
+        *self = rest;
+        Ok(amount)
+    }
+}
+
+impl<R: Read + ?Sized> Read for &mut R {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        (**self).read(buf)
+    }
+}
+
+impl Write for Vec<u8> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl Write for &mut [u8] {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        let amount = buf.len().min(self.len());
+        let (written, rest) = core::mem::take(self).split_at_mut(amount);
+        written.copy_from_slice(&buf[..amount]);
+        *self = rest;
+        Ok(amount)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<W: Write + ?Sized> Write for &mut W {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        (**self).write(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        (**self).flush()
+    }
+}
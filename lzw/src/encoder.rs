@@ -1,52 +1,108 @@
 //! Contains the implementation of fixed and variable code length encoders.
 
-use std::{
-    fmt::Display,
-    io::{Read, Write},
-};
+use alloc::{vec, vec::Vec};
+use core::fmt::Display;
 
 use crate::{
-    io::{BigEndianWriter, BitWriter, LittleEndianWriter},
-    CodeSizeStrategy, Endianness,
+    io::{
+        self, read_chunk, BigEndianWriter, BitWriter, CodeWriter, CountingWriter,
+        DifferencingReader, LittleEndianWriter, Read, SubBlockWriter, Write,
+    },
+    CodeSize, CodeSizeStrategy, Dictionary, DictionaryPolicy, Endianness,
 };
 
 /// The error type for encoding operations.
 ///
-/// Encapsulate [std::io::Error] and expose LZW code size or unexpected data issues.
+/// Encapsulate [io::Error] and expose LZW code size or unexpected data issues.
 #[derive(Debug)]
 pub enum EncodingError {
     /// An I/O error happened when reading or writing data.
-    Io(std::io::Error),
-    /// Code size out of bounds. It should be between 2 and 8 included.
+    Io(io::Error),
+    /// Code size out of bounds. It should be between 1 and 8 included, or at most 15 when
+    /// encoding symbols wider than a byte.
     CodeSize(u8),
+    /// Maximum code size out of bounds.
+    /// It should be bigger than the initial write size, and at most 16.
+    MaxCodeSize(u8),
     /// An unexpected code was read.
     ///
     /// For a code size of 4 for example,
     /// we expect the data to be between 0 and 2.pow(4) = 16.
     /// If in the data, we would then try to encode 42, it would not be correct and we return this
     /// unexpected code error.
-    UnexpectedCode { code: u8, code_size: u8 },
+    UnexpectedCode { code: u16, code_size: u8 },
+    /// Initial symbol count out of bounds.
+    /// It should be between 2 and 2.pow(code_size) included.
+    Possibilities(u16),
+    /// The preset dictionary doesn't fit the other settings: one of its bytes is not a symbol
+    /// of the initial dictionary, or it leaves no room for the dictionary to grow.
+    InvalidDictionary,
 }
 
 impl Display for EncodingError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
-            EncodingError::Io(error) => std::fmt::Display::fmt(&error, f),
+            EncodingError::Io(error) => core::fmt::Display::fmt(&error, f),
             EncodingError::CodeSize(code_size) => f.write_fmt(format_args!(
-                "Code size must be between 2 and 8, was {code_size}.",
+                "Code size must be between 1 and 8 (or up to 15 for symbols), was {code_size}.",
+            )),
+            EncodingError::MaxCodeSize(max_code_size) => f.write_fmt(format_args!(
+                "Maximum code size must be at most 16, and bigger than the initial write size, was {max_code_size}.",
             )),
             EncodingError::UnexpectedCode { code, code_size } => f.write_fmt(format_args!(
                 "Unexpected code {code}. For code size {code_size}, data should be < {}.",
                 (1 << code_size)
             )),
+            EncodingError::Possibilities(possibilities) => f.write_fmt(format_args!(
+                "Initial symbol count must be between 2 and 2.pow(code size), was {possibilities}.",
+            )),
+            EncodingError::InvalidDictionary => {
+                f.write_str("Preset dictionary not fitting the code size.")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EncodingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EncodingError::Io(error) => Some(error),
+            _ => None,
         }
     }
 }
 
-impl std::error::Error for EncodingError {}
+/// Errors are equal if they are the same variant with the same values. As [io::Error] can't be
+/// compared, two [EncodingError::Io] are equal if their [io::ErrorKind] are.
+impl PartialEq for EncodingError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (EncodingError::Io(error), EncodingError::Io(other)) => error.kind() == other.kind(),
+            (EncodingError::CodeSize(code_size), EncodingError::CodeSize(other)) => {
+                code_size == other
+            }
+            (EncodingError::MaxCodeSize(max_code_size), EncodingError::MaxCodeSize(other)) => {
+                max_code_size == other
+            }
+            (
+                EncodingError::UnexpectedCode { code, code_size },
+                EncodingError::UnexpectedCode {
+                    code: other_code,
+                    code_size: other_code_size,
+                },
+            ) => code == other_code && code_size == other_code_size,
+            (EncodingError::Possibilities(possibilities), EncodingError::Possibilities(other)) => {
+                possibilities == other
+            }
+            (EncodingError::InvalidDictionary, EncodingError::InvalidDictionary) => true,
+            _ => false,
+        }
+    }
+}
 
-impl From<std::io::Error> for EncodingError {
-    fn from(error: std::io::Error) -> Self {
+impl From<io::Error> for EncodingError {
+    fn from(error: io::Error) -> Self {
         EncodingError::Io(error)
     }
 }
@@ -55,47 +111,56 @@ impl From<std::io::Error> for EncodingError {
 /// or one children. Then it reduces logarithmically. Using an enum to represent these three cases
 /// seem to give the best return, preventing unecessary allocation of vecs.
 /// We will only allocate vec to store information of multiple leaves if an item gets more than one child.
+/// With more than 256 symbols, a vec indexed by symbol gets too big: the children are then kept
+/// sorted by symbol instead.
 #[derive(Debug, Clone)]
 enum Node {
     NoChild,
-    OneChild(u8, u16),
+    OneChild(u16, u16),
     ManyChildren(Vec<u16>),
+    SortedChildren(Vec<(u16, u16)>),
 }
 
 /// Inspired by trie: https://en.wikipedia.org/wiki/Trie
 /// Using this suggestion: https://dev.to/deciduously/no-more-tears-no-more-knots-arena-allocated-trees-in-rust-44k6
+#[derive(Debug, Clone)]
 pub(crate) struct Tree {
     nodes: Vec<Node>,
-    code_size: u8,
+    /// How many single symbol entries the tree starts with.
     code_count: usize,
-    with_clear_code: bool,
+    special_codes: usize,
 }
 
 impl Tree {
-    fn new(code_size: u8, with_clear_code: bool) -> Self {
+    /// The special codes are the codes reserved after the single symbol entries,
+    /// like the clear and end of information codes.
+    fn new(code_size: u8, special_codes: usize) -> Self {
         const MAX_ENTRY_COUNT: usize = 4097;
         let nodes = Vec::with_capacity(MAX_ENTRY_COUNT);
         let code_count = 1 << code_size;
         Self {
             nodes,
-            code_size,
             code_count,
-            with_clear_code,
+            special_codes,
         }
     }
 
     #[inline(always)]
     fn reset(&mut self) {
         self.nodes.clear();
-        if self.with_clear_code {
-            self.nodes.resize((1 << self.code_size) + 2, Node::NoChild);
-        } else {
-            self.nodes.resize(1 << self.code_size, Node::NoChild);
-        }
+        self.nodes
+            .resize(self.code_count + self.special_codes, Node::NoChild);
+    }
+
+    /// Switch to another amount of single symbol entries, resetting the tree while keeping
+    /// its allocation.
+    fn set_code_count(&mut self, code_count: usize) {
+        self.code_count = code_count;
+        self.reset();
     }
 
     #[inline(always)]
-    fn find_word(&self, prefix_index: u16, next_char: u8) -> Option<u16> {
+    fn find_word(&self, prefix_index: u16, next_char: u16) -> Option<u16> {
         let prefix = &self.nodes[prefix_index as usize];
         match prefix {
             Node::NoChild => None,
@@ -114,12 +179,19 @@ impl Tree {
                     None
                 }
             }
+            Node::SortedChildren(children) => children
+                .binary_search_by_key(&next_char, |&(child_char, _)| child_char)
+                .ok()
+                .map(|position| children[position].1),
         }
     }
 
+    /// Returns the index of the new entry. With 16 bit codes, it can be 65536: that entry is
+    /// never written, as the tree gets reset right away.
     #[inline(always)]
-    fn add(&mut self, prefix_index: u16, k: u8) -> u16 {
-        let new_index = self.nodes.len() as u16;
+    fn add(&mut self, prefix_index: u16, k: u16) -> usize {
+        let index = self.nodes.len();
+        let new_index = index as u16;
         let prefix_index = prefix_index as usize;
 
         let mut old_node = &mut self.nodes[prefix_index];
@@ -128,18 +200,28 @@ impl Tree {
             Node::NoChild => {
                 self.nodes[prefix_index] = Node::OneChild(k, new_index);
             }
-            Node::OneChild(other_k, other_index) => {
+            Node::OneChild(other_k, other_index) if self.code_count <= 256 => {
                 let mut children = vec![0; self.code_count];
                 children[*other_k as usize] = *other_index;
                 children[k as usize] = new_index;
                 self.nodes[prefix_index] = Node::ManyChildren(children);
             }
+            Node::OneChild(other_k, other_index) => {
+                let mut children = vec![(*other_k, *other_index), (k, new_index)];
+                children.sort_unstable();
+                self.nodes[prefix_index] = Node::SortedChildren(children);
+            }
             Node::ManyChildren(children) => {
                 children[k as usize] = new_index;
             }
+            Node::SortedChildren(children) => {
+                // A word is only added once, so `k` is not there yet.
+                let position = children.partition_point(|&(child_char, _)| child_char < k);
+                children.insert(position, (k, new_index));
+            }
         };
         self.nodes.push(Node::NoChild);
-        new_index
+        index
     }
 
     #[inline(always)]
@@ -148,8 +230,355 @@ impl Tree {
     }
 }
 
+/// Size of the buffer the data to compress is read into, rather than reading it byte by byte.
+const READ_BUFFER_SIZE: usize = 8192;
+
+/// Runs of the same byte at least this long skip the tree lookups, see
+/// [VariableState::push_run]. Shorter runs are not worth walking the chain of run words.
+const MIN_RUN_LENGTH: usize = 16;
+
+/// TIFF always uses a code size of 8, meaning it starts writing codes of 9 bits.
+const TIFF_CODE_SIZE: u8 = 8;
+/// PDF's `LZWDecode` filter starts writing codes of 9 bits as well.
+const PDF_CODE_SIZE: u8 = 8;
+/// GIF and TIFF both cap the write size at 12 bits, for a dictionary of 4096 entries.
+const MAX_CODE_SIZE: u8 = 12;
+/// Symbols leave room for at least one more bit, so that codes fit in 16 bits.
+const MAX_SYMBOL_SIZE: u8 = 15;
+
+/// The settings of the [VariableEncoder], covering the less common variations of LZW.
+///
+/// The default options are the GIF ones, for a code size of 8.
+///
+/// # Examples
+/// ```
+/// use salzweg::encoder::EncodingOptions;
+///
+/// let options = EncodingOptions {
+///     code_size: 2,
+///     leading_clear_code: false,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug)]
+pub struct EncodingOptions {
+    /// Between 1 and 8, the initial code size to use.
+    /// The initial write size will be equal to code size + 1.
+    pub code_size: u8,
+    /// At most 16, and at least the initial write size: the maximum size of the written codes.
+    pub max_code_size: u8,
+    /// Bit ordering when writing compressed data.
+    pub endianness: Endianness,
+    /// The strategy to use for increasing the code size.
+    pub code_size_strategy: CodeSizeStrategy,
+    /// Whether the stream starts with a clear code. GIF and TIFF both expect it, so it should
+    /// only be turned off for framings that start directly with the first data code.
+    pub leading_clear_code: bool,
+    /// What to do once the dictionary is full.
+    pub dictionary_policy: DictionaryPolicy,
+    /// Between 2 and 2.pow(code_size), how many symbols the initial dictionary holds, when the
+    /// data uses less than the 2.pow(code_size) values the code size allows for, like the 5
+    /// colors of a palette. The clear and end of information codes come right after them,
+    /// leaving more room for words in the dictionary. The data should be below it.
+    ///
+    /// `None` for the whole 2.pow(code_size) symbols, as GIF and TIFF expect.
+    pub possibilities: Option<u16>,
+    /// Words to seed the dictionary with, before any data and after each clear code. The
+    /// decoder needs the same [Dictionary].
+    ///
+    /// `None` for a dictionary starting with the single symbols only, as GIF and TIFF expect.
+    pub dictionary: Option<Dictionary>,
+    /// Whether the output is flushed once the data is encoded. The last byte is padded and
+    /// written either way: turning this off only keeps a buffered output, like a
+    /// `BufWriter` more data follows in, from being flushed. On by default.
+    pub flush: bool,
+}
+
+impl Default for EncodingOptions {
+    fn default() -> Self {
+        Self {
+            code_size: 8,
+            max_code_size: MAX_CODE_SIZE,
+            endianness: Endianness::LittleEndian,
+            code_size_strategy: CodeSizeStrategy::Default,
+            leading_clear_code: true,
+            dictionary_policy: DictionaryPolicy::Reset,
+            possibilities: None,
+            dictionary: None,
+            flush: true,
+        }
+    }
+}
+
+/// What happened while encoding, as reported by [VariableEncoder::encode_with_stats].
+///
+/// Handy to pick a code size: frequent clear codes mean the data keeps filling the dictionary,
+/// which hurts the compression ratio.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EncodeStats {
+    /// How many codes were written, clear and end of information codes included.
+    pub codes_emitted: usize,
+    /// How many clear codes were written, the leading one included.
+    pub clear_codes: usize,
+    /// How many times the write size grew by one bit.
+    pub code_size_increases: usize,
+    /// How many bytes were written to the output.
+    pub bytes_written: usize,
+}
+
+/// An empty output for the `encode_to_vec` helpers, with room for the length of `data` when it
+/// is known: compressed data rarely ends up bigger. Capped, as the length is only a hint.
+fn encoded_output<R: Read>(data: &mut R) -> Vec<u8> {
+    Vec::with_capacity(io::remaining_hint(data).min(io::MAX_RESERVED_OUTPUT))
+}
+
+fn check_code_sizes(code_size: u8, max_code_size: u8) -> Result<(), EncodingError> {
+    check_code_sizes_up_to(code_size, max_code_size, 8)
+}
+
+/// Same as [check_code_sizes], for symbols of up to `max_symbol_size` bits.
+fn check_code_sizes_up_to(
+    code_size: u8,
+    max_code_size: u8,
+    max_symbol_size: u8,
+) -> Result<(), EncodingError> {
+    if !(1..=max_symbol_size).contains(&code_size) {
+        return Err(EncodingError::CodeSize(code_size));
+    }
+    if !(code_size + 1..=16).contains(&max_code_size) {
+        return Err(EncodingError::MaxCodeSize(max_code_size));
+    }
+
+    Ok(())
+}
+
+/// GIF starts at a code size of 2, even for a bilevel image: the GIF presets reject 1.
+fn gif_code_size(code_size: u8) -> Result<CodeSize, EncodingError> {
+    if !(2..=8).contains(&code_size) {
+        return Err(EncodingError::CodeSize(code_size));
+    }
+
+    Ok(CodeSize(code_size))
+}
+
+/// Returns how many symbols the initial dictionary holds, for an already checked code size.
+fn check_possibilities(code_size: u8, possibilities: Option<u16>) -> Result<u16, EncodingError> {
+    match possibilities {
+        None => Ok(1 << code_size),
+        Some(possibilities) if (2..=1 << code_size).contains(&possibilities) => Ok(possibilities),
+        Some(possibilities) => Err(EncodingError::Possibilities(possibilities)),
+    }
+}
+
+/// Returns the entries a preset dictionary adds after the single symbols, if any, for already
+/// checked settings.
+fn check_dictionary(
+    dictionary: Option<&Dictionary>,
+    possibilities: u16,
+    max_code_size: u8,
+    code_size_strategy: CodeSizeStrategy,
+) -> Result<Vec<(u16, u8)>, EncodingError> {
+    match dictionary {
+        None => Ok(vec![]),
+        Some(dictionary) => dictionary
+            .codes(possibilities, max_code_size, code_size_strategy)
+            .ok_or(EncodingError::InvalidDictionary),
+    }
+}
+
+/// A single place to configure the encoding of variable code size LZW.
+///
+/// Starts from the GIF settings for a code size of 8, or from one of the presets, then each
+/// option can be changed before [EncoderBuilder::build] checks them. [GifStyleEncoder] and
+/// [TiffStyleEncoder] encode with the [EncoderBuilder::gif] and [EncoderBuilder::tiff]
+/// presets.
+///
+/// The options describing the stream are the ones of [crate::decoder::DecoderBuilder], to
+/// decode it back with the same settings. [EncoderBuilder::leading_clear_code] is the
+/// counterpart of [DecoderBuilder::strict](crate::decoder::DecoderBuilder::strict), and
+/// [EncoderBuilder::flush] has none, as the decoders always flush their output.
+///
+/// # Examples
+/// ```
+/// use salzweg::{
+///     encoder::{EncoderBuilder, EncodingError},
+///     CodeSize, CodeSizeStrategy, Endianness,
+/// };
+///
+/// fn main() -> Result<(), EncodingError> {
+///     let data = [0, 0, 1, 3];
+///     let mut output = vec![];
+///
+///     EncoderBuilder::new()
+///         .code_size(CodeSize::new(2)?)
+///         .endianness(Endianness::LittleEndian)
+///         .strategy(CodeSizeStrategy::Default)
+///         .build()?
+///         .encode(&data[..], &mut output)?;
+///
+///     assert_eq!(output, [0x04, 0x32, 0x05]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct EncoderBuilder {
+    options: EncodingOptions,
+}
+
+impl EncoderBuilder {
+    /// Create a builder with the GIF settings, for a code size of 8.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The settings of GIF: little endian, with the default code size increase strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `code_size` - The initial code size to use. GIF files need at least 2, which the
+    ///   [GifStyleEncoder] checks, but any [CodeSize] goes here, down to 1 for bilevel data.
+    pub fn gif(code_size: CodeSize) -> Self {
+        Self::new().code_size(code_size)
+    }
+
+    /// The settings of TIFF: a code size of 8, big endian, with the TIFF code size increase
+    /// strategy.
+    pub fn tiff() -> Self {
+        Self::new()
+            .code_size(CodeSize(TIFF_CODE_SIZE))
+            .endianness(Endianness::BigEndian)
+            .strategy(CodeSizeStrategy::Tiff)
+    }
+
+    /// Between 1 and 8, the initial code size to use.
+    /// The initial write size will be equal to code size + 1.
+    pub fn code_size(mut self, code_size: CodeSize) -> Self {
+        self.options.code_size = code_size.get();
+        self
+    }
+
+    /// At most 16, and at least the initial write size: the maximum size of the written codes.
+    /// GIF and TIFF use a maximum code size of 12.
+    pub fn max_code_size(mut self, max_code_size: u8) -> Self {
+        self.options.max_code_size = max_code_size;
+        self
+    }
+
+    /// Bit ordering when writing compressed data.
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.options.endianness = endianness;
+        self
+    }
+
+    /// The strategy to use for increasing the code size.
+    pub fn strategy(mut self, code_size_strategy: CodeSizeStrategy) -> Self {
+        self.options.code_size_strategy = code_size_strategy;
+        self
+    }
+
+    /// Whether the write size increases one code early, as the PDF `EarlyChange` parameter.
+    /// Another way to pick the strategy: `true` is [CodeSizeStrategy::Tiff], `false` is
+    /// [CodeSizeStrategy::Default].
+    pub fn early_change(self, early_change: bool) -> Self {
+        self.strategy(if early_change {
+            CodeSizeStrategy::Tiff
+        } else {
+            CodeSizeStrategy::Default
+        })
+    }
+
+    /// Whether the stream starts with a clear code. GIF and TIFF both expect it.
+    ///
+    /// A decoder checks for it with
+    /// [DecoderBuilder::strict](crate::decoder::DecoderBuilder::strict).
+    pub fn leading_clear_code(mut self, leading_clear_code: bool) -> Self {
+        self.options.leading_clear_code = leading_clear_code;
+        self
+    }
+
+    /// What to do once the dictionary is full.
+    pub fn dictionary_policy(mut self, dictionary_policy: DictionaryPolicy) -> Self {
+        self.options.dictionary_policy = dictionary_policy;
+        self
+    }
+
+    /// How many symbols the initial dictionary holds, when the data uses less than the
+    /// 2.pow(code_size) values the code size allows for.
+    pub fn possibilities(mut self, possibilities: u16) -> Self {
+        self.options.possibilities = Some(possibilities);
+        self
+    }
+
+    /// Words to seed the dictionary with, that the decoder needs as well.
+    pub fn dictionary(mut self, dictionary: Dictionary) -> Self {
+        self.options.dictionary = Some(dictionary);
+        self
+    }
+
+    /// Whether the output is flushed once the data is encoded. On by default.
+    ///
+    /// Encoder only: the decoders always flush their output once done.
+    pub fn flush(mut self, flush: bool) -> Self {
+        self.options.flush = flush;
+        self
+    }
+
+    /// Check the settings, returning an encoder ready to use them.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the code size, the max code size, the initial symbol count or
+    /// the preset dictionary is not supported.
+    pub fn build(self) -> Result<ConfiguredEncoder, EncodingError> {
+        check_code_sizes(self.options.code_size, self.options.max_code_size)?;
+        let possibilities =
+            check_possibilities(self.options.code_size, self.options.possibilities)?;
+        check_dictionary(
+            self.options.dictionary.as_ref(),
+            possibilities,
+            self.options.max_code_size,
+            self.options.code_size_strategy,
+        )?;
+
+        Ok(ConfiguredEncoder {
+            options: self.options,
+        })
+    }
+}
+
+/// A variable code size encoder with checked settings, created by [EncoderBuilder::build].
+#[derive(Debug)]
+pub struct ConfiguredEncoder {
+    options: EncodingOptions,
+}
+
+impl ConfiguredEncoder {
+    /// Compress `data` into `into`.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error] or for unexpected codes.
+    pub fn encode<R: Read, W: Write>(self, data: R, into: W) -> Result<(), EncodingError> {
+        VariableEncoder::encode_with_options(data, into, self.options)
+    }
+
+    /// Compress `data`.
+    /// Convenient wrapper that creates a [Vec<u8>] under the hood.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error] or for unexpected codes.
+    pub fn encode_to_vec<R: Read>(self, data: R) -> Result<Vec<u8>, EncodingError> {
+        let mut data = data;
+        let mut output = encoded_output(&mut data);
+        self.encode(data, &mut output)?;
+        Ok(output)
+    }
+}
+
 /// LZW encoder with variable code size. Generic implementation, prefer usage of
 /// the [GifStyleEncoder] or [TiffStyleEncoder] if they fit your needs better.
+#[derive(Debug, Clone, Copy, Default)]
 pub struct VariableEncoder;
 
 impl VariableEncoder {
@@ -159,19 +588,22 @@ impl VariableEncoder {
     ///
     /// * `data` - The source data to be compressed.
     /// * `into` - The output where compressed data should be written.
-    /// * `code_size` - Between 2 and 8, the initial code size to use.
+    /// * `code_size` - Between 1 and 8, the initial code size to use.
     ///   Initial code size correspond to the range of expected data.
     ///   For example, let's say we are compressing an ASCII string.
     ///   An ASCII string consist of bytes with values between 0 and 127, so 128 possibilities.
     ///   A code size of 7 means that we expect 2.pow(7) == 128 possibilities.
     ///   It would then provide the best compression.
     ///   The initial write size will be equal to code size + 1.
+    /// * `max_code_size` - At most 16, and at least the initial write size: the maximum size
+    ///   of the written codes. Once the dictionary is full, a clear code is written.
+    ///   GIF and TIFF use a maximum code size of 12.
     /// * `endianness` - Bit ordering when writing compressed data.
     /// * `code_size_strategy` - The strategy to use for increasing the code style.
     ///
     /// # Errors
     ///
-    /// This function can fail on an [std::io::Error] or for unexpected codes or code sizes.
+    /// This function can fail on an [io::Error] or for unexpected codes or code sizes.
     ///
     /// # Examples
     /// ```
@@ -188,6 +620,7 @@ impl VariableEncoder {
     ///         &data[..],
     ///         &mut output,
     ///         2,
+    ///         12,
     ///         Endianness::LittleEndian,
     ///         CodeSizeStrategy::Default,
     ///     )?;
@@ -200,44 +633,44 @@ impl VariableEncoder {
         data: R,
         into: W,
         code_size: u8,
+        max_code_size: u8,
         endianness: Endianness,
         code_size_strategy: CodeSizeStrategy,
     ) -> Result<(), EncodingError> {
-        match endianness {
-            Endianness::BigEndian => VariableEncoder::inner_encode(
-                data,
-                BigEndianWriter::new(into),
-                code_size,
-                code_size_strategy,
-            ),
-            Endianness::LittleEndian => VariableEncoder::inner_encode(
-                data,
-                LittleEndianWriter::new(into),
+        VariableEncoder::encode_with_options(
+            data,
+            into,
+            EncodingOptions {
                 code_size,
+                max_code_size,
+                endianness,
                 code_size_strategy,
-            ),
-        }
+                leading_clear_code: true,
+                dictionary_policy: DictionaryPolicy::Reset,
+                possibilities: None,
+                dictionary: None,
+                flush: true,
+            },
+        )
     }
 
-    /// Encode lzw, with variable code size.
-    /// Convenient wrapper that creates a [Vec<u8>] under the hood.
+    /// Same as [VariableEncoder::encode], handing back the reader and the writer once done,
+    /// to keep using an owned stream, like a file the rest of a container is written to.
     ///
     /// # Arguments
     ///
-    /// * `data` - The source data to be compressed.
-    /// * `code_size` - Between 2 and 8, the initial code size to use.
-    ///   Initial code size correspond to the range of expected data.
-    ///   For example, let's say we are compressing an ASCII string.
-    ///   An ASCII string consist of bytes with values between 0 and 127, so 128 possibilities.
-    ///   A code size of 7 means that we expect 2.pow(7) == 128 possibilities.
-    ///   It would then provide the best compression.
+    /// * `data` - The source data to be compressed. It is read until its end.
+    /// * `into` - The output where compressed data should be written.
+    /// * `code_size` - Between 1 and 8, the initial code size to use.
     ///   The initial write size will be equal to code size + 1.
+    /// * `max_code_size` - At most 16, and at least the initial write size: the maximum size
+    ///   of the written codes. GIF and TIFF use a maximum code size of 12.
     /// * `endianness` - Bit ordering when writing compressed data.
     /// * `code_size_strategy` - The strategy to use for increasing the code style.
     ///
     /// # Errors
     ///
-    /// This function can fail on an [std::io::Error], unexpected codes or code sizes.
+    /// This function can fail on an [io::Error] or for unexpected codes or code sizes.
     ///
     /// # Examples
     /// ```
@@ -248,419 +681,3279 @@ impl VariableEncoder {
     ///
     /// fn main() -> Result<(), EncodingError> {
     ///     let data = [0, 0, 1, 3];
-    ///     let output = VariableEncoder::encode_to_vec(
+    ///
+    ///     let (_, mut output) = VariableEncoder::encode_returning(
     ///         &data[..],
+    ///         vec![],
     ///         2,
+    ///         12,
     ///         Endianness::LittleEndian,
     ///         CodeSizeStrategy::Default,
     ///     )?;
+    ///     output.push(0);
     ///
-    ///     assert_eq!(output, [0x04, 0x32, 0x05]);
+    ///     assert_eq!(output, [0x04, 0x32, 0x05, 0]);
     ///     Ok(())
     /// }
     /// ```
-    pub fn encode_to_vec<R: Read>(
+    pub fn encode_returning<R: Read, W: Write>(
         data: R,
+        into: W,
         code_size: u8,
+        max_code_size: u8,
         endianness: Endianness,
         code_size_strategy: CodeSizeStrategy,
-    ) -> Result<Vec<u8>, EncodingError> {
-        let mut output = vec![];
-        VariableEncoder::encode(data, &mut output, code_size, endianness, code_size_strategy)?;
-        Ok(output)
-    }
-
-    fn inner_encode<R: Read, B: BitWriter>(
-        data: R,
-        bit_writer: B,
-        code_size: u8,
-        code_size_increase: CodeSizeStrategy,
-    ) -> Result<(), EncodingError> {
-        const MAX_WRITE_SIZE: u8 = 12;
-
-        if !(2..=8).contains(&code_size) {
-            return Err(EncodingError::CodeSize(code_size));
-        }
-
-        let max_code: u8 = ((1u32 << code_size) - 1) as u8;
-
-        let mut bit_writer = bit_writer;
-
-        let mut write_size = code_size + 1;
-        let clear_code = 1 << code_size;
-        let end_of_information = (1 << code_size) + 1;
-        let mut size_increase_mask = (1 << write_size) - code_size_increase.increment();
-
-        let mut tree = Tree::new(code_size, true);
-        tree.reset();
-
-        bit_writer.write(clear_code, write_size)?;
-
-        let mut bytes = data.bytes();
-        let k = bytes.next();
-        if k.is_none() {
-            // Well, it's an empty stream! Leaving early.
-            bit_writer.write(end_of_information, write_size)?;
+    ) -> Result<(R, W), EncodingError> {
+        let mut data = data;
+        let mut into = into;
 
-            bit_writer.fill()?;
-            bit_writer.flush()?;
-
-            return Ok(());
-        }
-
-        let mut current_prefix = k.unwrap()? as u16;
-
-        for k in bytes {
-            let k = k?;
-            if k > max_code {
-                return Err(EncodingError::UnexpectedCode { code: k, code_size });
-            }
-
-            if let Some(word) = tree.find_word(current_prefix, k) {
-                current_prefix = word;
-            } else {
-                let index_of_new_entry = tree.add(current_prefix, k);
-                bit_writer.write(current_prefix, write_size)?;
-                current_prefix = k as u16;
-
-                if index_of_new_entry == size_increase_mask {
-                    if write_size < MAX_WRITE_SIZE {
-                        write_size += 1;
-                    } else {
-                        bit_writer.write(clear_code, MAX_WRITE_SIZE)?;
-                        write_size = code_size + 1;
-                        tree.reset();
-                    }
-                    size_increase_mask = (1 << write_size) - code_size_increase.increment();
-                }
-            }
-        }
-
-        bit_writer.write(current_prefix, write_size)?;
-        bit_writer.write(end_of_information, write_size)?;
-
-        bit_writer.fill()?;
-        bit_writer.flush()?;
+        VariableEncoder::encode(
+            &mut data,
+            &mut into,
+            code_size,
+            max_code_size,
+            endianness,
+            code_size_strategy,
+        )?;
 
-        Ok(())
+        Ok((data, into))
     }
-}
-
-/// LZW encoder tuned for GIF.
-///
-/// Its code size is between 2 and 8 included, and the data will be
-/// written using little endian packing in the output [std::io::Write].
-pub struct GifStyleEncoder;
 
-impl GifStyleEncoder {
-    /// Compress data with LZW, using GIF style variable encoding.
+    /// Same as [VariableEncoder::encode], handing the codes to a custom [BitWriter] rather
+    /// than packing them in bytes of either endianness.
+    ///
+    /// Each code is written with the current write size. Once the end of information code is
+    /// written, the bit writer is filled then flushed.
     ///
     /// # Arguments
     ///
     /// * `data` - The source data to be compressed.
-    /// * `into` - The output where compressed data should be written.
-    /// * `code_size` - Between 2 and 8, the initial code size to use.
-    ///   Initial code size correspond to the range of expected data.
-    ///   For example, let's say we are compressing an ASCII string.
-    ///   An ASCII string consist of bytes with values between 0 and 127, so 128 possibilities.
-    ///   A code size of 7 means that we expect 2.pow(7) == 128 possibilities.
-    ///   It would then provide the best compression.
+    /// * `bit_writer` - Where the codes are written.
+    /// * `code_size` - Between 1 and 8, the initial code size to use.
     ///   The initial write size will be equal to code size + 1.
+    /// * `max_code_size` - At most 16, and at least the initial write size: the maximum size
+    ///   of the written codes. GIF and TIFF use a maximum code size of 12.
+    /// * `code_size_strategy` - The strategy to use for increasing the code style.
     ///
     /// # Errors
     ///
-    /// This function can fail on an [std::io::Error], unexpected codes or code sizes.
+    /// This function can fail on an [io::Error] or for unexpected codes or code sizes.
     ///
     /// # Examples
-    ///
     /// ```
     /// use salzweg::{
-    ///     encoder::{EncodingError, GifStyleEncoder, VariableEncoder},
-    ///     CodeSizeStrategy, Endianness,
+    ///     encoder::{EncodingError, VariableEncoder},
+    ///     io::{BitWriter, Error},
+    ///     CodeSizeStrategy,
     /// };
     ///
+    /// /// Keeps the codes along with their size, rather than packing them.
+    /// struct CodeList(Vec<(u16, u8)>);
+    ///
+    /// impl BitWriter for CodeList {
+    ///     fn write(&mut self, data: u16, amount: u8) -> Result<(), Error> {
+    ///         self.0.push((data, amount));
+    ///         Ok(())
+    ///     }
+    ///
+    ///     fn fill(&mut self) -> Result<(), Error> {
+    ///         Ok(())
+    ///     }
+    ///
+    ///     fn flush(&mut self) -> Result<(), Error> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
     /// fn main() -> Result<(), EncodingError> {
     ///     let data = [0, 0, 1, 3];
-    ///     let mut output = vec![];
+    ///     let mut codes = CodeList(vec![]);
     ///
-    ///     GifStyleEncoder::encode(&data[..], &mut output, 2)?;
+    ///     VariableEncoder::encode_with_writer(&data[..], &mut codes, 2, 12, CodeSizeStrategy::Default)?;
     ///
-    ///     assert_eq!(output, [0x04, 0x32, 0x05]);
+    ///     assert_eq!(codes.0, [(4, 3), (0, 3), (0, 3), (1, 3), (3, 4), (5, 4)]);
     ///     Ok(())
     /// }
     /// ```
-    pub fn encode<R: Read, W: Write>(data: R, into: W, code_size: u8) -> Result<(), EncodingError> {
+    pub fn encode_with_writer<R: Read, B: BitWriter>(
+        data: R,
+        bit_writer: B,
+        code_size: u8,
+        max_code_size: u8,
+        code_size_strategy: CodeSizeStrategy,
+    ) -> Result<(), EncodingError> {
         VariableEncoder::inner_encode(
             data,
-            LittleEndianWriter::new(into),
+            bit_writer,
             code_size,
-            CodeSizeStrategy::Default,
+            max_code_size,
+            code_size_strategy,
+            true,
+            DictionaryPolicy::Reset,
         )
     }
 
-    /// Compress data with LZW, using GIF style variable encoding.
-    /// Convenient wrapper that creates a [Vec<u8>] under the hood.
+    /// How many bytes [VariableEncoder::encode] would write for `data`, without keeping them.
+    ///
+    /// This is not a shortcut: the data is fully compressed, only the codes are counted
+    /// rather than packed. The size is exact, and the same for both endiannesses.
     ///
     /// # Arguments
     ///
     /// * `data` - The source data to be compressed.
-    /// * `code_size` - Between 2 and 8, the initial code size to use.
-    ///   Initial code size correspond to the range of expected data.
-    ///   For example, let's say we are compressing an ASCII string.
-    ///   An ASCII string consist of bytes with values between 0 and 127, so 128 possibilities.
-    ///   A code size of 7 means that we expect 2.pow(7) == 128 possibilities.
-    ///   It would then provide the best compression.
+    /// * `code_size` - Between 1 and 8, the initial code size to use.
     ///   The initial write size will be equal to code size + 1.
+    /// * `max_code_size` - At most 16, and at least the initial write size: the maximum size
+    ///   of the written codes. GIF and TIFF use a maximum code size of 12.
+    /// * `code_size_strategy` - The strategy to use for increasing the code style.
     ///
     /// # Errors
     ///
-    /// This function can fail on an [std::io::Error], unexpected codes or code sizes.
+    /// This function can fail on an [io::Error] or for unexpected codes or code sizes.
     ///
     /// # Examples
-    ///
     /// ```
     /// use salzweg::{
-    ///     encoder::{EncodingError, GifStyleEncoder, VariableEncoder},
-    ///     CodeSizeStrategy, Endianness,
+    ///     encoder::{EncodingError, VariableEncoder},
+    ///     CodeSizeStrategy,
     /// };
     ///
     /// fn main() -> Result<(), EncodingError> {
     ///     let data = [0, 0, 1, 3];
-    ///     let output = GifStyleEncoder::encode_to_vec(&data[..], 2)?;
     ///
-    ///     assert_eq!(output, [0x04, 0x32, 0x05]);
+    ///     let size = VariableEncoder::estimate_size(&data[..], 2, 12, CodeSizeStrategy::Default)?;
+    ///
+    ///     assert_eq!(size, 3);
     ///     Ok(())
     /// }
     /// ```
-    pub fn encode_to_vec<R: Read>(data: R, code_size: u8) -> Result<Vec<u8>, EncodingError> {
-        let mut output = vec![];
-        GifStyleEncoder::encode(data, &mut output, code_size)?;
-        Ok(output)
-    }
-}
-
-/// LZW encoder tuned for TIFF.
-///
-/// Variable code size, it starts at a write size of 9 bits, and will use big endian packing
-/// in the output [std::io::Write].
-pub struct TiffStyleEncoder;
+    pub fn estimate_size<R: Read>(
+        data: R,
+        code_size: u8,
+        max_code_size: u8,
+        code_size_strategy: CodeSizeStrategy,
+    ) -> Result<usize, EncodingError> {
+        let mut counter = BitCounter::default();
+        VariableEncoder::encode_with_writer(
+            data,
+            &mut counter,
+            code_size,
+            max_code_size,
+            code_size_strategy,
+        )?;
 
-impl TiffStyleEncoder {
-    /// Encode lzw, with variable code size, using the TIFF style.
-    ///
-    /// Code write size will start at 9, and be written in big endian ordering.
+        Ok(counter.bits.div_ceil(8) as usize)
+    }
+
+    /// Encode lzw, with variable code size, for the variations not covered by
+    /// [VariableEncoder::encode].
     ///
     /// # Arguments
     ///
     /// * `data` - The source data to be compressed.
     /// * `into` - The output where compressed data should be written.
+    /// * `options` - The settings of the encoder.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error] or for unexpected codes or code sizes.
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::encoder::{EncodingError, EncodingOptions, VariableEncoder};
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![];
+    ///
+    ///     let options = EncodingOptions {
+    ///         code_size: 2,
+    ///         leading_clear_code: false,
+    ///         ..Default::default()
+    ///     };
+    ///     VariableEncoder::encode_with_options(&data[..], &mut output, options)?;
+    ///
+    ///     assert_eq!(output, [0x40, 0xa6, 0x0]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_with_options<R: Read, W: Write>(
+        data: R,
+        into: W,
+        options: EncodingOptions,
+    ) -> Result<(), EncodingError> {
+        let EncodingOptions {
+            code_size,
+            max_code_size,
+            endianness,
+            code_size_strategy,
+            leading_clear_code,
+            dictionary_policy,
+            possibilities,
+            dictionary,
+            flush,
+        } = options;
+        check_code_sizes(code_size, max_code_size)?;
+        let possibilities = check_possibilities(code_size, possibilities)?;
+        let preset = check_dictionary(
+            dictionary.as_ref(),
+            possibilities,
+            max_code_size,
+            code_size_strategy,
+        )?;
+
+        let mut state = VariableState::with_tree(
+            Tree::new(code_size, 2),
+            code_size,
+            possibilities,
+            max_code_size,
+            code_size_strategy,
+            dictionary_policy,
+        );
+        state.set_preset(preset);
+
+        match endianness {
+            Endianness::BigEndian => VariableEncoder::encode_with_state(
+                data,
+                BigEndianWriter::buffered(into),
+                &mut state,
+                leading_clear_code,
+                flush,
+            ),
+            Endianness::LittleEndian => VariableEncoder::encode_with_state(
+                data,
+                LittleEndianWriter::buffered(into),
+                &mut state,
+                leading_clear_code,
+                flush,
+            ),
+        }
+    }
+
+    /// Encode lzw, with variable code size.
+    /// Convenient wrapper that creates a [Vec<u8>] under the hood.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `code_size` - Between 1 and 8, the initial code size to use.
+    ///   Initial code size correspond to the range of expected data.
+    ///   For example, let's say we are compressing an ASCII string.
+    ///   An ASCII string consist of bytes with values between 0 and 127, so 128 possibilities.
+    ///   A code size of 7 means that we expect 2.pow(7) == 128 possibilities.
+    ///   It would then provide the best compression.
+    ///   The initial write size will be equal to code size + 1.
+    /// * `max_code_size` - At most 16, and at least the initial write size: the maximum size
+    ///   of the written codes. Once the dictionary is full, a clear code is written.
+    ///   GIF and TIFF use a maximum code size of 12.
+    /// * `endianness` - Bit ordering when writing compressed data.
+    /// * `code_size_strategy` - The strategy to use for increasing the code style.
     ///
     /// # Errors
     ///
-    /// This function can fail on an [std::io::Error].
+    /// This function can fail on an [io::Error], unexpected codes or code sizes.
     ///
     /// # Examples
     /// ```
     /// use salzweg::{
-    ///     encoder::{EncodingError, TiffStyleEncoder},
+    ///     encoder::{EncodingError, VariableEncoder},
     ///     CodeSizeStrategy, Endianness,
     /// };
     ///
     /// fn main() -> Result<(), EncodingError> {
     ///     let data = [0, 0, 1, 3];
-    ///     let mut output = vec![];
+    ///     let output = VariableEncoder::encode_to_vec(
+    ///         &data[..],
+    ///         2,
+    ///         12,
+    ///         Endianness::LittleEndian,
+    ///         CodeSizeStrategy::Default,
+    ///     )?;
     ///
-    ///     TiffStyleEncoder::encode(&data[..], &mut output)?;
+    ///     assert_eq!(output, [0x04, 0x32, 0x05]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_to_vec<R: Read>(
+        data: R,
+        code_size: u8,
+        max_code_size: u8,
+        endianness: Endianness,
+        code_size_strategy: CodeSizeStrategy,
+    ) -> Result<Vec<u8>, EncodingError> {
+        let mut data = data;
+        let mut output = encoded_output(&mut data);
+        VariableEncoder::encode(
+            data,
+            &mut output,
+            code_size,
+            max_code_size,
+            endianness,
+            code_size_strategy,
+        )?;
+        Ok(output)
+    }
+
+    /// Encode lzw, with variable code size.
+    /// Reuses an existing [Vec<u8>]: it is cleared first, keeping its capacity.
     ///
-    ///     assert_eq!(output, [0x80, 0x0, 0x0, 0x0, 0x10, 0x1c, 0x4]);
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The vector where compressed data will be written, after clearing it.
+    /// * `code_size` - Between 1 and 8, the initial code size to use.
+    ///   The initial write size will be equal to code size + 1.
+    /// * `max_code_size` - At most 16, and at least the initial write size: the maximum size
+    ///   of the written codes. GIF and TIFF use a maximum code size of 12.
+    /// * `endianness` - Bit ordering when writing compressed data.
+    /// * `code_size_strategy` - The strategy to use for increasing the code style.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error] or for unexpected codes or code sizes.
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::{
+    ///     encoder::{EncodingError, VariableEncoder},
+    ///     CodeSizeStrategy, Endianness,
+    /// };
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![42; 8];
+    ///
+    ///     VariableEncoder::encode_into_vec(
+    ///         &data[..],
+    ///         &mut output,
+    ///         2,
+    ///         12,
+    ///         Endianness::LittleEndian,
+    ///         CodeSizeStrategy::Default,
+    ///     )?;
+    ///
+    ///     assert_eq!(output, [0x04, 0x32, 0x05]);
     ///     Ok(())
     /// }
     /// ```
-    pub fn encode<R: Read, W: Write>(data: R, into: W) -> Result<(), EncodingError> {
-        const TIFF_CODE_SIZE: u8 = 8;
-        VariableEncoder::inner_encode(
+    pub fn encode_into_vec<R: Read>(
+        data: R,
+        into: &mut Vec<u8>,
+        code_size: u8,
+        max_code_size: u8,
+        endianness: Endianness,
+        code_size_strategy: CodeSizeStrategy,
+    ) -> Result<(), EncodingError> {
+        into.clear();
+        VariableEncoder::encode(
             data,
-            BigEndianWriter::new(into),
-            TIFF_CODE_SIZE,
-            CodeSizeStrategy::Tiff,
+            into,
+            code_size,
+            max_code_size,
+            endianness,
+            code_size_strategy,
         )
     }
 
-    /// Encode lzw, with variable code size, using the TIFF style.
-    /// Convenient wrapper that creates a [Vec<u8>] under the hood.
+    /// Encode lzw, with variable code size, stopping short of packing the codes into bytes.
     ///
-    /// Code write size will start at 9, and be written in big endian ordering.
+    /// Returns the sequence of dictionary codes, clear and end of information codes included,
+    /// handy to inspect a stream or to build a custom container. The maximum code size is 12.
+    /// [crate::decoder::VariableDecoder::decode_from_codes] turns them back into bytes.
     ///
     /// # Arguments
     ///
     /// * `data` - The source data to be compressed.
+    /// * `code_size` - Between 1 and 8, the initial code size to use.
+    ///   The initial code width will be equal to code size + 1.
+    /// * `code_size_strategy` - The strategy to use for increasing the code style.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error] or for unexpected codes or code sizes.
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::{
+    ///     encoder::{EncodingError, VariableEncoder},
+    ///     CodeSizeStrategy,
+    /// };
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let codes = VariableEncoder::encode_codes(&data[..], 2, CodeSizeStrategy::Default)?;
+    ///
+    ///     assert_eq!(codes, [4, 0, 0, 1, 3, 5]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_codes<R: Read>(
+        data: R,
+        code_size: u8,
+        code_size_strategy: CodeSizeStrategy,
+    ) -> Result<Vec<u16>, EncodingError> {
+        let mut codes = vec![];
+        VariableEncoder::inner_encode(
+            data,
+            CodeWriter::new(&mut codes),
+            code_size,
+            MAX_CODE_SIZE,
+            code_size_strategy,
+            true,
+            DictionaryPolicy::Reset,
+        )?;
+        Ok(codes)
+    }
+
+    /// Encode lzw, with variable code size, taking symbols wider than a byte.
+    ///
+    /// LZW works the same with any alphabet: the single symbols fill the first entries of the
+    /// dictionary, here up to 2.pow(15) of them. Handy to compress data that was already
+    /// tokenized, or more than 8 bit samples. [crate::decoder::VariableDecoder::decode_symbols]
+    /// gives the symbols back.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbols` - The symbols to be compressed, all below 2.pow(code_size).
     /// * `into` - The output where compressed data should be written.
+    /// * `code_size` - Between 1 and 15, the initial code size to use.
+    ///   The initial write size will be equal to code size + 1.
+    /// * `max_code_size` - At most 16, and at least the initial write size: the maximum size
+    ///   of the written codes. Once the dictionary is full, a clear code is written.
+    /// * `endianness` - Bit ordering when writing compressed data.
+    /// * `code_size_strategy` - The strategy to use for increasing the code style.
     ///
     /// # Errors
     ///
-    /// This function can fail on an [std::io::Error].
+    /// This function can fail on an [io::Error] or for unexpected codes or code sizes.
     ///
     /// # Examples
     /// ```
     /// use salzweg::{
-    ///     encoder::{EncodingError, TiffStyleEncoder},
+    ///     decoder::VariableDecoder,
+    ///     encoder::{EncodingError, VariableEncoder},
     ///     CodeSizeStrategy, Endianness,
     /// };
     ///
     /// fn main() -> Result<(), EncodingError> {
-    ///     let data = [0, 0, 1, 3];
+    ///     let symbols = [0, 0, 300, 511, 300, 511];
+    ///     let mut output = vec![];
     ///
-    ///     let output = TiffStyleEncoder::encode_to_vec(&data[..])?;
+    ///     VariableEncoder::encode_symbols(
+    ///         &symbols,
+    ///         &mut output,
+    ///         9,
+    ///         12,
+    ///         Endianness::LittleEndian,
+    ///         CodeSizeStrategy::Default,
+    ///     )?;
     ///
-    ///     assert_eq!(output, [0x80, 0x0, 0x0, 0x0, 0x10, 0x1c, 0x4]);
+    ///     let decoded = VariableDecoder::decode_symbols(
+    ///         &output[..],
+    ///         9,
+    ///         12,
+    ///         Endianness::LittleEndian,
+    ///         CodeSizeStrategy::Default,
+    ///     )
+    ///     .expect("Decompression failed");
+    ///     assert_eq!(decoded, symbols);
     ///     Ok(())
     /// }
     /// ```
-    pub fn encode_to_vec<R: Read>(data: R) -> Result<Vec<u8>, EncodingError> {
-        let mut output = vec![];
-        TiffStyleEncoder::encode(data, &mut output)?;
-        Ok(output)
-    }
-}
+    pub fn encode_symbols<W: Write>(
+        symbols: &[u16],
+        into: W,
+        code_size: u8,
+        max_code_size: u8,
+        endianness: Endianness,
+        code_size_strategy: CodeSizeStrategy,
+    ) -> Result<(), EncodingError> {
+        check_code_sizes_up_to(code_size, max_code_size, MAX_SYMBOL_SIZE)?;
 
-/// LZW encoder writing fixed 12 bit codes.
-///
-/// There is no clear or end of information codes: As soon as the dictionary is full,
-/// we stop increasing its size.
-pub struct FixedEncoder;
+        let mut state = VariableState::new(
+            code_size,
+            max_code_size,
+            code_size_strategy,
+            DictionaryPolicy::Reset,
+        );
+        fn push_all<B: BitWriter>(
+            symbols: &[u16],
+            state: &mut VariableState,
+            bit_writer: &mut B,
+        ) -> Result<(), EncodingError> {
+            for &k in symbols {
+                state.push_symbol(k, bit_writer)?;
+            }
+            Ok(())
+        }
 
-impl FixedEncoder {
-    /// Compress data with LZW outputting fixed code of 12 bits.
-    ///
-    /// For really small amount data like the one showcased in the example, the compressed might
-    /// take more space than the original data. Variable code size compression is generally better.
+        match endianness {
+            Endianness::BigEndian => VariableEncoder::encode_fed_by(
+                BigEndianWriter::buffered(into),
+                &mut state,
+                true,
+                true,
+                |state, bit_writer| push_all(symbols, state, bit_writer),
+            ),
+            Endianness::LittleEndian => VariableEncoder::encode_fed_by(
+                LittleEndianWriter::buffered(into),
+                &mut state,
+                true,
+                true,
+                |state, bit_writer| push_all(symbols, state, bit_writer),
+            ),
+        }
+    }
+
+    /// Encode lzw, with variable code size, taking the data from an iterator rather than a
+    /// [Read]. The maximum code size is 12.
     ///
     /// # Arguments
     ///
-    /// * `data` - The source data to be compressed.
+    /// * `iter` - The bytes to be compressed.
     /// * `into` - The output where compressed data should be written.
+    /// * `code_size` - Between 1 and 8, the initial code size to use.
+    ///   The initial write size will be equal to code size + 1.
     /// * `endianness` - Bit ordering when writing compressed data.
+    /// * `code_size_strategy` - The strategy to use for increasing the code style.
     ///
     /// # Errors
     ///
-    /// This function can fail on an [std::io::Error].
+    /// This function can fail on an [io::Error] or for unexpected codes or code sizes.
     ///
     /// # Examples
     /// ```
     /// use salzweg::{
-    ///     encoder::{EncodingError, FixedEncoder},
+    ///     encoder::{EncodingError, VariableEncoder},
     ///     CodeSizeStrategy, Endianness,
     /// };
     ///
     /// fn main() -> Result<(), EncodingError> {
-    ///     let data = [0, 0, 1, 3];
+    ///     let pixels = [0, 0, 1, 3].iter().copied();
     ///     let mut output = vec![];
     ///
-    ///     FixedEncoder::encode(&data[..], &mut output, Endianness::LittleEndian)?;
+    ///     VariableEncoder::encode_from_iter(
+    ///         pixels,
+    ///         &mut output,
+    ///         2,
+    ///         Endianness::LittleEndian,
+    ///         CodeSizeStrategy::Default,
+    ///     )?;
     ///
-    ///     assert_eq!(output, [0x0, 0x0, 0x0, 0x1, 0x30, 0x0]);
+    ///     assert_eq!(output, [0x04, 0x32, 0x05]);
     ///     Ok(())
     /// }
     /// ```
-    pub fn encode<R: Read, W: Write>(
-        data: R,
+    pub fn encode_from_iter<I: IntoIterator<Item = u8>, W: Write>(
+        iter: I,
         into: W,
+        code_size: u8,
         endianness: Endianness,
+        code_size_strategy: CodeSizeStrategy,
     ) -> Result<(), EncodingError> {
+        check_code_sizes(code_size, MAX_CODE_SIZE)?;
+
+        let mut state = VariableState::new(
+            code_size,
+            MAX_CODE_SIZE,
+            code_size_strategy,
+            DictionaryPolicy::Reset,
+        );
+        fn push_all<I: Iterator<Item = u8>, B: BitWriter>(
+            iter: I,
+            state: &mut VariableState,
+            bit_writer: &mut B,
+        ) -> Result<(), EncodingError> {
+            for k in iter {
+                state.push(k, bit_writer)?;
+            }
+            Ok(())
+        }
+        let iter = iter.into_iter();
+
         match endianness {
-            Endianness::BigEndian => FixedEncoder::inner_encode(data, BigEndianWriter::new(into)),
-            Endianness::LittleEndian => {
-                FixedEncoder::inner_encode(data, LittleEndianWriter::new(into))
+            Endianness::BigEndian => VariableEncoder::encode_fed_by(
+                BigEndianWriter::buffered(into),
+                &mut state,
+                true,
+                true,
+                |state, bit_writer| push_all(iter, state, bit_writer),
+            ),
+            Endianness::LittleEndian => VariableEncoder::encode_fed_by(
+                LittleEndianWriter::buffered(into),
+                &mut state,
+                true,
+                true,
+                |state, bit_writer| push_all(iter, state, bit_writer),
+            ),
+        }
+    }
+
+    /// Encode lzw, with variable code size, like [VariableEncoder::encode_with_options],
+    /// reporting some statistics about the encoding.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The output where compressed data should be written.
+    /// * `options` - The settings of the encoder.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error] or for unexpected codes or code sizes.
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::encoder::{EncodeStats, EncodingError, EncodingOptions, VariableEncoder};
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![];
+    ///
+    ///     let options = EncodingOptions {
+    ///         code_size: 2,
+    ///         ..Default::default()
+    ///     };
+    ///     let stats = VariableEncoder::encode_with_stats(&data[..], &mut output, options)?;
+    ///
+    ///     assert_eq!(output, [0x04, 0x32, 0x05]);
+    ///     assert_eq!(
+    ///         stats,
+    ///         EncodeStats {
+    ///             codes_emitted: 6,
+    ///             clear_codes: 1,
+    ///             code_size_increases: 1,
+    ///             bytes_written: 3,
+    ///         }
+    ///     );
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_with_stats<R: Read, W: Write>(
+        data: R,
+        into: W,
+        options: EncodingOptions,
+    ) -> Result<EncodeStats, EncodingError> {
+        let EncodingOptions {
+            code_size,
+            max_code_size,
+            endianness,
+            code_size_strategy,
+            leading_clear_code,
+            dictionary_policy,
+            possibilities,
+            dictionary,
+            flush,
+        } = options;
+        check_code_sizes(code_size, max_code_size)?;
+        let possibilities = check_possibilities(code_size, possibilities)?;
+        let preset = check_dictionary(
+            dictionary.as_ref(),
+            possibilities,
+            max_code_size,
+            code_size_strategy,
+        )?;
+
+        let mut into = CountingWriter::new(into);
+        let mut state = VariableState::with_tree(
+            Tree::new(code_size, 2),
+            code_size,
+            possibilities,
+            max_code_size,
+            code_size_strategy,
+            dictionary_policy,
+        );
+        state.set_preset(preset);
+
+        match endianness {
+            Endianness::BigEndian => VariableEncoder::encode_with_state(
+                data,
+                BigEndianWriter::buffered(&mut into),
+                &mut state,
+                leading_clear_code,
+                flush,
+            )?,
+            Endianness::LittleEndian => VariableEncoder::encode_with_state(
+                data,
+                LittleEndianWriter::buffered(&mut into),
+                &mut state,
+                leading_clear_code,
+                flush,
+            )?,
+        }
+
+        Ok(EncodeStats {
+            bytes_written: into.bytes_written_usize()?,
+            ..state.stats
+        })
+    }
+
+    fn inner_encode<R: Read, B: BitWriter>(
+        data: R,
+        bit_writer: B,
+        code_size: u8,
+        max_code_size: u8,
+        code_size_increase: CodeSizeStrategy,
+        leading_clear_code: bool,
+        dictionary_policy: DictionaryPolicy,
+    ) -> Result<(), EncodingError> {
+        check_code_sizes(code_size, max_code_size)?;
+
+        let mut state = VariableState::new(
+            code_size,
+            max_code_size,
+            code_size_increase,
+            dictionary_policy,
+        );
+
+        VariableEncoder::encode_with_state(data, bit_writer, &mut state, leading_clear_code, true)
+    }
+
+    fn encode_with_state<R: Read, B: BitWriter>(
+        data: R,
+        bit_writer: B,
+        state: &mut VariableState,
+        leading_clear_code: bool,
+        flush: bool,
+    ) -> Result<(), EncodingError> {
+        VariableEncoder::encode_fed_by(
+            bit_writer,
+            state,
+            leading_clear_code,
+            flush,
+            |state, bit_writer| {
+                let mut data = data;
+                let mut buffer = [0; READ_BUFFER_SIZE];
+                loop {
+                    let read = read_chunk(&mut data, &mut buffer)?;
+                    if read == 0 {
+                        return Ok(());
+                    }
+                    state.push_slice(&buffer[..read], bit_writer)?;
+                }
+            },
+        )
+    }
+
+    /// The encoding shared by all the sources of data: `feed` pushes every byte to the state.
+    fn encode_fed_by<B, F>(
+        bit_writer: B,
+        state: &mut VariableState,
+        leading_clear_code: bool,
+        flush: bool,
+        feed: F,
+    ) -> Result<(), EncodingError>
+    where
+        B: BitWriter,
+        F: FnOnce(&mut VariableState, &mut B) -> Result<(), EncodingError>,
+    {
+        let mut bit_writer = bit_writer;
+
+        if leading_clear_code {
+            state.start(&mut bit_writer)?;
+        }
+
+        feed(state, &mut bit_writer)?;
+
+        state.finish(&mut bit_writer)?;
+
+        bit_writer.fill()?;
+        if flush {
+            bit_writer.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Counts the bits of the codes instead of packing them. Filling only pads the last byte, so
+/// the bytes written are the bits rounded up.
+#[derive(Default)]
+struct BitCounter {
+    bits: u64,
+}
+
+impl BitWriter for BitCounter {
+    #[inline]
+    fn write(&mut self, _data: u16, amount: u8) -> Result<(), io::Error> {
+        self.bits += amount as u64;
+        Ok(())
+    }
+
+    fn fill(&mut self) -> Result<(), io::Error> {
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        Ok(())
+    }
+}
+
+/// The dictionary of a [VariableEncoder], kept around to encode many times in a row.
+///
+/// Every call to [VariableEncoder::encode] allocates a new dictionary. When compressing many
+/// independent buffers, this state keeps the allocation of its dictionary from one encode
+/// to the next, only resetting its content.
+///
+/// # Examples
+/// ```
+/// use salzweg::encoder::{EncodingError, EncodingOptions, VariableEncoderState};
+///
+/// fn main() -> Result<(), EncodingError> {
+///     let buffers: [&[u8]; 2] = [&[0, 0, 1, 3], &[0, 0, 0, 0]];
+///     let options = || EncodingOptions {
+///         code_size: 2,
+///         ..Default::default()
+///     };
+///
+///     let mut state = VariableEncoderState::new();
+///     let mut outputs = vec![];
+///     for buffer in buffers {
+///         let mut output = vec![];
+///         state.encode_reusing(buffer, &mut output, options())?;
+///         outputs.push(output);
+///     }
+///
+///     assert_eq!(outputs[0], [0x04, 0x32, 0x05]);
+///     assert_eq!(outputs[1], [0x84, 0x51]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct VariableEncoderState {
+    tree: Option<Tree>,
+}
+
+impl VariableEncoderState {
+    /// Create an empty state, its dictionary gets allocated by the first encode.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encode lzw, with variable code size, like [VariableEncoder::encode_with_options], but
+    /// reusing the dictionary of this state.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The output where compressed data will be written.
+    /// * `options` - The settings of the encoder.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error] or for unexpected codes or code sizes.
+    pub fn encode_reusing<R: Read, W: Write>(
+        &mut self,
+        data: R,
+        into: W,
+        options: EncodingOptions,
+    ) -> Result<(), EncodingError> {
+        let EncodingOptions {
+            code_size,
+            max_code_size,
+            endianness,
+            code_size_strategy,
+            leading_clear_code,
+            dictionary_policy,
+            possibilities,
+            dictionary,
+            flush,
+        } = options;
+        check_code_sizes(code_size, max_code_size)?;
+        let possibilities = check_possibilities(code_size, possibilities)?;
+        let preset = check_dictionary(
+            dictionary.as_ref(),
+            possibilities,
+            max_code_size,
+            code_size_strategy,
+        )?;
+
+        let tree = self.tree.take().unwrap_or_else(|| Tree::new(code_size, 2));
+        let mut state = VariableState::with_tree(
+            tree,
+            code_size,
+            possibilities,
+            max_code_size,
+            code_size_strategy,
+            dictionary_policy,
+        );
+        state.set_preset(preset);
+
+        let result = match endianness {
+            Endianness::BigEndian => VariableEncoder::encode_with_state(
+                data,
+                BigEndianWriter::buffered(into),
+                &mut state,
+                leading_clear_code,
+                flush,
+            ),
+            Endianness::LittleEndian => VariableEncoder::encode_with_state(
+                data,
+                LittleEndianWriter::buffered(into),
+                &mut state,
+                leading_clear_code,
+                flush,
+            ),
+        };
+        // Even when failing, the dictionary is kept for the next encode.
+        self.tree = Some(state.tree);
+
+        result
+    }
+}
+
+/// The dictionary and code size bookkeeping of a variable code size encoder.
+///
+/// Everything needed to resume the compression lives here, so the same state can be fed
+/// in one go by [VariableEncoder], or piece by piece by the streaming [Encoder].
+#[derive(Clone)]
+struct VariableState {
+    tree: Tree,
+    code_size: u8,
+    max_code: u16,
+    clear_code: u16,
+    end_of_information: u16,
+    code_size_increment: usize,
+    write_size: u8,
+    max_write_size: u8,
+    size_increase_mask: usize,
+    dictionary_policy: DictionaryPolicy,
+    current_prefix: Option<u16>,
+    /// The words made of a single byte repeated, from 1 to as many times as the tree holds,
+    /// while pushing a run of that byte. Kept to reuse its allocation.
+    run_words: Vec<u16>,
+    /// Only the code counters are kept up to date, not the amount of bytes written.
+    stats: EncodeStats,
+    /// The entries of the preset dictionary, added after the single symbols on each clear.
+    preset: Vec<(u16, u8)>,
+}
+
+impl VariableState {
+    /// The code size and maximum write size are expected to be already validated.
+    fn new(
+        code_size: u8,
+        max_write_size: u8,
+        code_size_increase: CodeSizeStrategy,
+        dictionary_policy: DictionaryPolicy,
+    ) -> Self {
+        VariableState::with_tree(
+            Tree::new(code_size, 2),
+            code_size,
+            1 << code_size,
+            max_write_size,
+            code_size_increase,
+            dictionary_policy,
+        )
+    }
+
+    /// Same as [VariableState::new], reusing the allocation of an existing tree, and with
+    /// `possibilities` single symbols, at most 2.pow(code_size), in the initial dictionary.
+    fn with_tree(
+        tree: Tree,
+        code_size: u8,
+        possibilities: u16,
+        max_write_size: u8,
+        code_size_increase: CodeSizeStrategy,
+        dictionary_policy: DictionaryPolicy,
+    ) -> Self {
+        let max_code = possibilities - 1;
+        // The special codes directly follow the single symbols.
+        let clear_code = possibilities;
+        let end_of_information = clear_code + 1;
+        let code_size_increment = code_size_increase.increment() as usize;
+        let write_size = code_size + 1;
+        let size_increase_mask = (1 << write_size) - code_size_increment;
+
+        let mut tree = tree;
+        tree.set_code_count(possibilities as usize);
+
+        Self {
+            tree,
+            code_size,
+            max_code,
+            clear_code,
+            end_of_information,
+            code_size_increment,
+            write_size,
+            max_write_size,
+            size_increase_mask,
+            dictionary_policy,
+            current_prefix: None,
+            run_words: vec![],
+            stats: EncodeStats::default(),
+            preset: vec![],
+        }
+    }
+
+    /// Seed the dictionary with the entries of a preset dictionary, checked beforehand.
+    fn set_preset(&mut self, preset: Vec<(u16, u8)>) {
+        self.preset = preset;
+        self.clear_dictionary();
+    }
+
+    /// Start over from the initial dictionary, and the write size it calls for.
+    fn clear_dictionary(&mut self) {
+        self.write_size = self.code_size + 1;
+        self.size_increase_mask = (1 << self.write_size) - self.code_size_increment;
+        self.tree.reset();
+        // The decoder grows its read size the same way, while seeding its own table.
+        for &(prefix, k) in &self.preset {
+            let index = self.tree.add(prefix, k.into());
+            if index >= self.size_increase_mask && self.write_size < self.max_write_size {
+                self.write_size += 1;
+                self.size_increase_mask = (1 << self.write_size) - self.code_size_increment;
+            }
+        }
+    }
+
+    fn start<B: BitWriter>(&mut self, bit_writer: &mut B) -> Result<(), EncodingError> {
+        bit_writer.write(self.clear_code, self.write_size)?;
+        self.stats.codes_emitted += 1;
+        self.stats.clear_codes += 1;
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn push<B: BitWriter>(&mut self, k: u8, bit_writer: &mut B) -> Result<(), EncodingError> {
+        self.push_symbol(k.into(), bit_writer)
+    }
+
+    /// Same as [VariableState::push], for symbols wider than a byte.
+    #[inline(always)]
+    fn push_symbol<B: BitWriter>(
+        &mut self,
+        k: u16,
+        bit_writer: &mut B,
+    ) -> Result<(), EncodingError> {
+        if k > self.max_code {
+            return Err(EncodingError::UnexpectedCode {
+                code: k,
+                code_size: self.code_size,
+            });
+        }
+
+        let current_prefix = match self.current_prefix {
+            Some(current_prefix) => current_prefix,
+            None => {
+                // Very first symbol of the stream, nothing to look up yet.
+                self.current_prefix = Some(k);
+                return Ok(());
+            }
+        };
+
+        if let Some(word) = self.tree.find_word(current_prefix, k) {
+            self.current_prefix = Some(word);
+        } else {
+            self.write_word(current_prefix, k, bit_writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Push every byte of `data`, going through runs of the same byte with
+    /// [VariableState::push_run].
+    fn push_slice<B: BitWriter>(
+        &mut self,
+        data: &[u8],
+        bit_writer: &mut B,
+    ) -> Result<(), EncodingError> {
+        let mut rest = data;
+        while let Some(&k) = rest.first() {
+            let run = rest.iter().take_while(|&&other| other == k).count();
+            if run < MIN_RUN_LENGTH {
+                for _ in 0..run {
+                    self.push(k, bit_writer)?;
+                }
+            } else {
+                self.push_run(k, run, bit_writer)?;
+            }
+            rest = &rest[run..];
+        }
+
+        Ok(())
+    }
+
+    /// Same as pushing `k` `count` times, without looking up the tree for each byte.
+    ///
+    /// Once the pending word is written, the run goes on from the single byte `k`: the words
+    /// it then goes through are `k` repeated over and over, the run words. Their codes are
+    /// collected once, after which each word written by the run is the longest of them,
+    /// extended by one more `k` as the next run word.
+    fn push_run<B: BitWriter>(
+        &mut self,
+        k: u8,
+        count: usize,
+        bit_writer: &mut B,
+    ) -> Result<(), EncodingError> {
+        if k as u16 > self.max_code {
+            return Err(EncodingError::UnexpectedCode {
+                code: k.into(),
+                code_size: self.code_size,
+            });
+        }
+
+        // The pending word, made of other bytes, goes through the tree as usual.
+        let mut remaining = count;
+        while remaining > 0 && self.current_prefix != Some(k as u16) {
+            self.push(k, bit_writer)?;
+            remaining -= 1;
+        }
+        if remaining == 0 {
+            return Ok(());
+        }
+
+        let mut run_words = core::mem::take(&mut self.run_words);
+        run_words.clear();
+        run_words.push(k as u16);
+        while let Some(word) = self
+            .tree
+            .find_word(run_words[run_words.len() - 1], k.into())
+        {
+            run_words.push(word);
+        }
+
+        // From the single byte `k`, each byte of the run moves to the next run word, until
+        // going past the longest one.
+        while remaining >= run_words.len() {
+            remaining -= run_words.len();
+            let longest = run_words[run_words.len() - 1];
+            match self.write_word(longest, k.into(), bit_writer)? {
+                // A reset tree only holds the single bytes, and the preset entries.
+                Some(index) if index >= self.tree.len() => {
+                    run_words.truncate(1);
+                    while let Some(word) = self
+                        .tree
+                        .find_word(run_words[run_words.len() - 1], k.into())
+                    {
+                        run_words.push(word);
+                    }
+                }
+                Some(index) => run_words.push(index as u16),
+                None => {}
+            }
+        }
+        self.current_prefix = Some(run_words[remaining]);
+
+        self.run_words = run_words;
+        Ok(())
+    }
+
+    /// Write `prefix`, a word the tree holds but not followed by `k`, then add that longer
+    /// word to the tree, unless frozen. Returns the index of the new entry, if any: past the
+    /// end of the tree when adding it made the tree reset.
+    ///
+    /// The pending word becomes the single symbol `k`.
+    #[inline(always)]
+    fn write_word<B: BitWriter>(
+        &mut self,
+        prefix: u16,
+        k: u16,
+        bit_writer: &mut B,
+    ) -> Result<Option<usize>, EncodingError> {
+        if self.is_frozen() {
+            bit_writer.write(prefix, self.write_size)?;
+            self.stats.codes_emitted += 1;
+            self.current_prefix = Some(k);
+            return Ok(None);
+        }
+
+        let index_of_new_entry = self.tree.add(prefix, k);
+        bit_writer.write(prefix, self.write_size)?;
+        self.stats.codes_emitted += 1;
+        self.current_prefix = Some(k);
+
+        // With a code size of 1, the very first new entry can already be past the
+        // increase point of the TIFF strategy.
+        if index_of_new_entry >= self.size_increase_mask {
+            if self.write_size < self.max_write_size {
+                self.write_size += 1;
+                self.stats.code_size_increases += 1;
+                self.size_increase_mask = (1 << self.write_size) - self.code_size_increment;
+            } else if let DictionaryPolicy::Reset = self.dictionary_policy {
+                bit_writer.write(self.clear_code, self.max_write_size)?;
+                self.stats.codes_emitted += 1;
+                self.stats.clear_codes += 1;
+                self.clear_dictionary();
+            }
+        }
+
+        Ok(Some(index_of_new_entry))
+    }
+
+    /// With the [DictionaryPolicy::Freeze] policy, whether the dictionary stopped growing.
+    #[inline(always)]
+    fn is_frozen(&self) -> bool {
+        match self.dictionary_policy {
+            DictionaryPolicy::Reset => false,
+            DictionaryPolicy::Freeze => self.tree.len() == 1 << self.max_write_size,
+        }
+    }
+
+    /// Write the pending word, if any, before a clear or end of information code.
+    fn write_pending_word<B: BitWriter>(
+        &mut self,
+        bit_writer: &mut B,
+    ) -> Result<(), EncodingError> {
+        if let Some(current_prefix) = self.current_prefix.take() {
+            bit_writer.write(current_prefix, self.write_size)?;
+            self.stats.codes_emitted += 1;
+            // Reading the pending word completes one more entry on the decoder side, which
+            // can be the one making its read size grow before the code that follows.
+            if self.tree.len() >= self.size_increase_mask && self.write_size < self.max_write_size {
+                self.write_size += 1;
+                self.stats.code_size_increases += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the pending word and a clear code, then start over from a fresh dictionary.
+    fn checkpoint<B: BitWriter>(&mut self, bit_writer: &mut B) -> Result<(), EncodingError> {
+        self.write_pending_word(bit_writer)?;
+        bit_writer.write(self.clear_code, self.write_size)?;
+        self.stats.codes_emitted += 1;
+        self.stats.clear_codes += 1;
+        self.clear_dictionary();
+
+        Ok(())
+    }
+
+    fn finish<B: BitWriter>(&mut self, bit_writer: &mut B) -> Result<(), EncodingError> {
+        self.write_pending_word(bit_writer)?;
+        bit_writer.write(self.end_of_information, self.write_size)?;
+        self.stats.codes_emitted += 1;
+
+        Ok(())
+    }
+}
+
+/// The bit writer of the streaming [Encoder]. Codes are packed in an internal buffer,
+/// which is then handed over to the output given to each call.
+#[derive(Clone)]
+enum ChunkWriter {
+    BigEndian(BigEndianWriter<Vec<u8>>),
+    LittleEndian(LittleEndianWriter<Vec<u8>>),
+}
+
+impl ChunkWriter {
+    fn new(endianness: Endianness) -> Self {
+        match endianness {
+            Endianness::BigEndian => ChunkWriter::BigEndian(BigEndianWriter::buffered(vec![])),
+            Endianness::LittleEndian => {
+                ChunkWriter::LittleEndian(LittleEndianWriter::buffered(vec![]))
+            }
+        }
+    }
+
+    fn drain_into<W: Write>(&mut self, into: &mut W) -> Result<(), io::Error> {
+        // Moves the bytes held back by the bit writer to its buffer.
+        self.flush()?;
+        let buffer = match self {
+            ChunkWriter::BigEndian(writer) => writer.get_mut(),
+            ChunkWriter::LittleEndian(writer) => writer.get_mut(),
+        };
+        into.write_all(buffer)?;
+        buffer.clear();
+
+        Ok(())
+    }
+}
+
+impl BitWriter for ChunkWriter {
+    #[inline]
+    fn write(&mut self, data: u16, amount: u8) -> Result<(), io::Error> {
+        match self {
+            ChunkWriter::BigEndian(writer) => writer.write(data, amount),
+            ChunkWriter::LittleEndian(writer) => writer.write(data, amount),
+        }
+    }
+
+    #[inline]
+    fn write_many(&mut self, codes: &[u16], amount: u8) -> Result<(), io::Error> {
+        match self {
+            ChunkWriter::BigEndian(writer) => writer.write_many(codes, amount),
+            ChunkWriter::LittleEndian(writer) => writer.write_many(codes, amount),
+        }
+    }
+
+    #[inline]
+    fn fill(&mut self) -> Result<(), io::Error> {
+        match self {
+            ChunkWriter::BigEndian(writer) => writer.fill(),
+            ChunkWriter::LittleEndian(writer) => writer.fill(),
+        }
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), io::Error> {
+        match self {
+            ChunkWriter::BigEndian(writer) => writer.flush(),
+            ChunkWriter::LittleEndian(writer) => writer.flush(),
+        }
+    }
+}
+
+/// Streaming LZW encoder with variable code size.
+///
+/// Where [VariableEncoder::encode] compresses all its data in one go, starting from a fresh
+/// dictionary each time, the [Encoder] keeps its dictionary and pending prefix between calls
+/// to [Encoder::encode_chunk]. Compressing `[a, b]` then `[c]` produces exactly the same
+/// output as compressing `[a, b, c]` at once.
+///
+/// The stream is only complete once [Encoder::finish] has been called. Until then, the
+/// pending word, the end of information code and the last partial byte are held by the
+/// encoder: dropping it loses them, and decoders then report the data as truncated. Nothing
+/// is written on drop, as an error could not be reported there, and nothing warns about it
+/// either: finishing the encoder is up to the caller.
+///
+/// Cloning an encoder forks the stream: the clone gets its own copy of the dictionary,
+/// and both can then be fed different data, to keep the one that compresses best.
+///
+/// # Examples
+/// ```
+/// use salzweg::encoder::{Encoder, EncodingError};
+///
+/// fn main() -> Result<(), EncodingError> {
+///     let mut output = vec![];
+///
+///     let mut encoder = Encoder::gif(2)?;
+///     encoder.encode_chunk(&[0, 0], &mut output)?;
+///     encoder.encode_chunk(&[1, 3], &mut output)?;
+///     encoder.finish(&mut output)?;
+///
+///     assert_eq!(output, [0x04, 0x32, 0x05]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Encoder {
+    state: VariableState,
+    bit_writer: ChunkWriter,
+}
+
+impl Encoder {
+    /// Create a streaming encoder, with variable code size. Generic implementation, prefer
+    /// usage of [Encoder::gif] or [Encoder::tiff] if they fit your needs better.
+    ///
+    /// # Arguments
+    ///
+    /// * `code_size` - Between 1 and 8, the initial code size to use.
+    ///   The initial write size will be equal to code size + 1.
+    /// * `endianness` - Bit ordering when writing compressed data.
+    /// * `code_size_strategy` - The strategy to use for increasing the code style.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the code size is not supported.
+    pub fn new(
+        code_size: u8,
+        endianness: Endianness,
+        code_size_strategy: CodeSizeStrategy,
+    ) -> Result<Self, EncodingError> {
+        check_code_sizes(code_size, MAX_CODE_SIZE)?;
+
+        Ok(Encoder::with_writer(
+            VariableState::new(
+                code_size,
+                MAX_CODE_SIZE,
+                code_size_strategy,
+                DictionaryPolicy::Reset,
+            ),
+            ChunkWriter::new(endianness),
+        ))
+    }
+
+    /// Create a streaming encoder tuned for GIF: little endian packing, and the default
+    /// code size increase strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `code_size` - Between 2 and 8, the initial code size to use.
+    ///   The initial write size will be equal to code size + 1.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the code size is not supported.
+    pub fn gif(code_size: u8) -> Result<Self, EncodingError> {
+        gif_code_size(code_size)?;
+        Encoder::new(
+            code_size,
+            Endianness::LittleEndian,
+            CodeSizeStrategy::Default,
+        )
+    }
+
+    /// Create a streaming encoder tuned for TIFF: code write size will start at 9,
+    /// and be written in big endian ordering.
+    pub fn tiff() -> Self {
+        Encoder::with_writer(
+            VariableState::new(
+                TIFF_CODE_SIZE,
+                MAX_CODE_SIZE,
+                CodeSizeStrategy::Tiff,
+                DictionaryPolicy::Reset,
+            ),
+            ChunkWriter::new(Endianness::BigEndian),
+        )
+    }
+
+    fn with_writer(state: VariableState, bit_writer: ChunkWriter) -> Self {
+        let mut encoder = Self { state, bit_writer };
+        // Writing to a vec can't fail.
+        let _ = encoder.state.start(&mut encoder.bit_writer);
+        encoder
+    }
+
+    /// Compress a chunk of data, continuing the stream where the previous chunk left it.
+    ///
+    /// The last word of the chunk might not be written right away, as the next chunk could
+    /// still extend it.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The next chunk of data to be compressed.
+    /// * `into` - The output where compressed data should be written.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error] or for unexpected codes.
+    pub fn encode_chunk<W: Write>(&mut self, data: &[u8], into: W) -> Result<(), EncodingError> {
+        let mut into = into;
+
+        self.state.push_slice(data, &mut self.bit_writer)?;
+        self.bit_writer.drain_into(&mut into)?;
+
+        Ok(())
+    }
+
+    /// The width, in bits, the next code will be written with.
+    ///
+    /// Handy to compare with another implementation, to spot where the two stop agreeing
+    /// on when the code size increases.
+    pub fn current_code_width(&self) -> u8 {
+        self.state.write_size
+    }
+
+    /// Force a boundary in the stream, without ending it: writes the pending word and a clear
+    /// code, resets the dictionary, and hands over the compressed data to `into`.
+    ///
+    /// Whatever follows doesn't depend on the data before the checkpoint, bounding how long a
+    /// decoder that lost some earlier bytes takes to resynchronize. It costs a bit of
+    /// compression, as the dictionary has to be rebuilt from scratch.
+    ///
+    /// Padding the last byte would insert bogus codes in the stream, so the last few bits of
+    /// the clear code, short of a full byte, are only written with the next chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `into` - The output where compressed data should be written.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error].
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::{
+    ///     decoder::GifStyleDecoder,
+    ///     encoder::{Encoder, EncodingError},
+    /// };
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let mut output = vec![];
+    ///
+    ///     let mut encoder = Encoder::gif(2)?;
+    ///     encoder.encode_chunk(&[0, 0], &mut output)?;
+    ///     encoder.checkpoint(&mut output)?;
+    ///     encoder.encode_chunk(&[1, 3], &mut output)?;
+    ///     encoder.finish(&mut output)?;
+    ///
+    ///     assert_eq!(GifStyleDecoder::decode_to_vec(&output[..], 2).unwrap(), [0, 0, 1, 3]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn checkpoint<W: Write>(&mut self, into: W) -> Result<(), EncodingError> {
+        let mut into = into;
+
+        self.state.checkpoint(&mut self.bit_writer)?;
+        self.bit_writer.drain_into(&mut into)?;
+        into.flush()?;
+
+        Ok(())
+    }
+
+    /// Terminate the stream: writes the pending word, the end of information code,
+    /// and pads the last byte.
+    ///
+    /// Required for the output to be a complete stream, see [Encoder].
+    ///
+    /// # Arguments
+    ///
+    /// * `into` - The output where compressed data should be written.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error].
+    pub fn finish<W: Write>(self, into: W) -> Result<(), EncodingError> {
+        let mut into = into;
+
+        self.finish_no_flush(&mut into)?;
+        into.flush()?;
+
+        Ok(())
+    }
+
+    /// Same as [Encoder::finish], without flushing `into`, to keep buffering whatever
+    /// follows the stream. The whole stream, last byte included, is still written to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `into` - The output where compressed data should be written.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error].
+    pub fn finish_no_flush<W: Write>(mut self, into: W) -> Result<(), EncodingError> {
+        let mut into = into;
+
+        self.state.finish(&mut self.bit_writer)?;
+        self.bit_writer.fill()?;
+        self.bit_writer.drain_into(&mut into)?;
+
+        Ok(())
+    }
+}
+
+/// LZW encoder tuned for GIF.
+///
+/// Its code size is between 2 and 8 included, and the data will be
+/// written using little endian packing in the output [Write].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GifStyleEncoder;
+
+impl GifStyleEncoder {
+    /// Compress data with LZW, using GIF style variable encoding.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The output where compressed data should be written.
+    /// * `code_size` - Between 2 and 8, the initial code size to use.
+    ///   Initial code size correspond to the range of expected data.
+    ///   For example, let's say we are compressing an ASCII string.
+    ///   An ASCII string consist of bytes with values between 0 and 127, so 128 possibilities.
+    ///   A code size of 7 means that we expect 2.pow(7) == 128 possibilities.
+    ///   It would then provide the best compression.
+    ///   The initial write size will be equal to code size + 1.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error], unexpected codes or code sizes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use salzweg::{
+    ///     encoder::{EncodingError, GifStyleEncoder, VariableEncoder},
+    ///     CodeSizeStrategy, Endianness,
+    /// };
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![];
+    ///
+    ///     GifStyleEncoder::encode(&data[..], &mut output, 2)?;
+    ///
+    ///     assert_eq!(output, [0x04, 0x32, 0x05]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode<R: Read, W: Write>(data: R, into: W, code_size: u8) -> Result<(), EncodingError> {
+        EncoderBuilder::gif(gif_code_size(code_size)?)
+            .build()?
+            .encode(data, into)
+    }
+
+    /// Compress data with LZW, using GIF style variable encoding, but packing the codes in
+    /// big endian order (most significant bit first) rather than GIF's little endian.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The output where compressed data should be written.
+    /// * `code_size` - Between 2 and 8, the initial code size to use.
+    ///   The initial write size will be equal to code size + 1.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error] or for unexpected codes or code sizes.
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::encoder::{EncodingError, GifStyleEncoder};
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![];
+    ///
+    ///     GifStyleEncoder::encode_msb(&data[..], &mut output, 2)?;
+    ///
+    ///     assert_eq!(output, [0x80, 0x13, 0x50]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_msb<R: Read, W: Write>(
+        data: R,
+        into: W,
+        code_size: u8,
+    ) -> Result<(), EncodingError> {
+        EncoderBuilder::gif(gif_code_size(code_size)?)
+            .endianness(Endianness::BigEndian)
+            .build()?
+            .encode(data, into)
+    }
+
+    /// Compress data with LZW, using GIF style variable encoding.
+    /// Convenient wrapper that creates a [Vec<u8>] under the hood.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `code_size` - Between 2 and 8, the initial code size to use.
+    ///   Initial code size correspond to the range of expected data.
+    ///   For example, let's say we are compressing an ASCII string.
+    ///   An ASCII string consist of bytes with values between 0 and 127, so 128 possibilities.
+    ///   A code size of 7 means that we expect 2.pow(7) == 128 possibilities.
+    ///   It would then provide the best compression.
+    ///   The initial write size will be equal to code size + 1.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error], unexpected codes or code sizes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use salzweg::{
+    ///     encoder::{EncodingError, GifStyleEncoder, VariableEncoder},
+    ///     CodeSizeStrategy, Endianness,
+    /// };
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let output = GifStyleEncoder::encode_to_vec(&data[..], 2)?;
+    ///
+    ///     assert_eq!(output, [0x04, 0x32, 0x05]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_to_vec<R: Read>(data: R, code_size: u8) -> Result<Vec<u8>, EncodingError> {
+        let mut data = data;
+        let mut output = encoded_output(&mut data);
+        GifStyleEncoder::encode(data, &mut output, code_size)?;
+        Ok(output)
+    }
+
+    /// Encode lzw, with variable code size, using the GIF style.
+    /// Reuses an existing [Vec<u8>]: it is cleared first, keeping its capacity.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The vector where compressed data will be written, after clearing it.
+    /// * `code_size` - Between 2 and 8, the initial code size to use.
+    ///   The initial write size will be equal to code size + 1.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error] or for unexpected codes or code sizes.
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::encoder::{EncodingError, GifStyleEncoder};
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![42; 8];
+    ///
+    ///     GifStyleEncoder::encode_into_vec(&data[..], &mut output, 2)?;
+    ///
+    ///     assert_eq!(output, [0x04, 0x32, 0x05]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_into_vec<R: Read>(
+        data: R,
+        into: &mut Vec<u8>,
+        code_size: u8,
+    ) -> Result<(), EncodingError> {
+        into.clear();
+        GifStyleEncoder::encode(data, into, code_size)
+    }
+
+    /// Encode lzw, with variable code size, using the GIF style.
+    /// Same as [GifStyleEncoder::encode], but returns the number of bytes written.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The output where compressed data should be written.
+    /// * `code_size` - Between 2 and 8, the initial code size to use.
+    ///   The initial write size will be equal to code size + 1.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error] or for unexpected codes or code sizes.
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::encoder::{EncodingError, GifStyleEncoder};
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![];
+    ///
+    ///     let written = GifStyleEncoder::encode_counted(&data[..], &mut output, 2)?;
+    ///
+    ///     assert_eq!(written, 3);
+    ///     assert_eq!(output, [0x04, 0x32, 0x05]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_counted<R: Read, W: Write>(
+        data: R,
+        into: W,
+        code_size: u8,
+    ) -> Result<usize, EncodingError> {
+        let mut into = CountingWriter::new(into);
+        GifStyleEncoder::encode(data, &mut into, code_size)?;
+        Ok(into.bytes_written_usize()?)
+    }
+
+    /// Compress data with LZW, using GIF style variable encoding, framed as it would
+    /// appear inside a GIF Image Data block.
+    ///
+    /// The output starts with the LZW minimum code size byte, followed by the compressed data
+    /// chopped into sub-blocks of at most 255 bytes, each prefixed by its length,
+    /// and ends with the block terminator: an empty sub-block.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The output where the framed compressed data should be written.
+    /// * `code_size` - Between 2 and 8, the initial code size to use.
+    ///   Initial code size correspond to the range of expected data.
+    ///   The initial write size will be equal to code size + 1.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error], unexpected codes or code sizes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use salzweg::encoder::{EncodingError, GifStyleEncoder};
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![];
+    ///
+    ///     GifStyleEncoder::encode_blocks(&data[..], &mut output, 2)?;
+    ///
+    ///     assert_eq!(output, [0x02, 0x03, 0x04, 0x32, 0x05, 0x00]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_blocks<R: Read, W: Write>(
+        data: R,
+        into: W,
+        code_size: u8,
+    ) -> Result<(), EncodingError> {
+        gif_code_size(code_size)?;
+
+        let mut into = into;
+        into.write_all(&[code_size])?;
+
+        let mut sub_block_writer = SubBlockWriter::new(into);
+        GifStyleEncoder::encode(data, &mut sub_block_writer, code_size)?;
+        sub_block_writer.finish()?;
+
+        Ok(())
+    }
+}
+
+/// LZW encoder tuned for TIFF.
+///
+/// Variable code size, it starts at a write size of 9 bits, and will use big endian packing
+/// in the output [Write].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TiffStyleEncoder;
+
+impl TiffStyleEncoder {
+    /// Encode lzw, with variable code size, using the TIFF style.
+    ///
+    /// Code write size will start at 9, and be written in big endian ordering.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The output where compressed data should be written.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error].
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::{
+    ///     encoder::{EncodingError, TiffStyleEncoder},
+    ///     CodeSizeStrategy, Endianness,
+    /// };
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![];
+    ///
+    ///     TiffStyleEncoder::encode(&data[..], &mut output)?;
+    ///
+    ///     assert_eq!(output, [0x80, 0x0, 0x0, 0x0, 0x10, 0x1c, 0x4]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode<R: Read, W: Write>(data: R, into: W) -> Result<(), EncodingError> {
+        EncoderBuilder::tiff().build()?.encode(data, into)
+    }
+
+    /// Encode lzw, with variable code size, using the TIFF style, choosing whether the write
+    /// size increases one code early.
+    ///
+    /// Compliant TIFF readers expect the early change, but some decoders increase the code
+    /// size at the GIF boundary instead: those need `early_change` off.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The output where compressed data should be written.
+    /// * `early_change` - When `true`, as [TiffStyleEncoder::encode] does, the write size
+    ///   increases one code early. When `false`, it increases like with GIF.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error].
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::{
+    ///     decoder::TiffStyleDecoder,
+    ///     encoder::{EncodingError, TiffStyleEncoder},
+    /// };
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![];
+    ///
+    ///     TiffStyleEncoder::encode_with_early_change(&data[..], &mut output, false)?;
+    ///
+    ///     let mut decoded = vec![];
+    ///     TiffStyleDecoder::decode_with_early_change(&output[..], &mut decoded, false).unwrap();
+    ///     assert_eq!(decoded, data);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_with_early_change<R: Read, W: Write>(
+        data: R,
+        into: W,
+        early_change: bool,
+    ) -> Result<(), EncodingError> {
+        EncoderBuilder::tiff()
+            .early_change(early_change)
+            .build()?
+            .encode(data, into)
+    }
+
+    /// Encode lzw, with variable code size, using the TIFF style.
+    /// Convenient wrapper that creates a [Vec<u8>] under the hood.
+    ///
+    /// Code write size will start at 9, and be written in big endian ordering.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The output where compressed data should be written.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error].
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::{
+    ///     encoder::{EncodingError, TiffStyleEncoder},
+    ///     CodeSizeStrategy, Endianness,
+    /// };
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///
+    ///     let output = TiffStyleEncoder::encode_to_vec(&data[..])?;
+    ///
+    ///     assert_eq!(output, [0x80, 0x0, 0x0, 0x0, 0x10, 0x1c, 0x4]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_to_vec<R: Read>(data: R) -> Result<Vec<u8>, EncodingError> {
+        let mut data = data;
+        let mut output = encoded_output(&mut data);
+        TiffStyleEncoder::encode(data, &mut output)?;
+        Ok(output)
+    }
+
+    /// Encode lzw, with variable code size, using the TIFF style.
+    /// Reuses an existing [Vec<u8>]: it is cleared first, keeping its capacity.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The vector where compressed data will be written, after clearing it.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error].
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::encoder::{EncodingError, TiffStyleEncoder};
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![42; 8];
+    ///
+    ///     TiffStyleEncoder::encode_into_vec(&data[..], &mut output)?;
+    ///
+    ///     assert_eq!(output, [0x80, 0x0, 0x0, 0x0, 0x10, 0x1c, 0x4]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_into_vec<R: Read>(data: R, into: &mut Vec<u8>) -> Result<(), EncodingError> {
+        into.clear();
+        TiffStyleEncoder::encode(data, into)
+    }
+
+    /// Encode lzw, with variable code size, using the TIFF style.
+    /// Same as [TiffStyleEncoder::encode], but returns the number of bytes written.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The output where compressed data should be written.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error].
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::encoder::{EncodingError, TiffStyleEncoder};
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![];
+    ///
+    ///     let written = TiffStyleEncoder::encode_counted(&data[..], &mut output)?;
+    ///
+    ///     assert_eq!(written, 7);
+    ///     assert_eq!(output, [0x80, 0x0, 0x0, 0x0, 0x10, 0x1c, 0x4]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_counted<R: Read, W: Write>(data: R, into: W) -> Result<usize, EncodingError> {
+        let mut into = CountingWriter::new(into);
+        TiffStyleEncoder::encode(data, &mut into)?;
+        Ok(into.bytes_written_usize()?)
+    }
+
+    /// Encode the strips of a TIFF image, each one as an independent TIFF style LZW stream,
+    /// written one after the other.
+    ///
+    /// Each strip starts with a clear code and a fresh dictionary, and ends with its own end
+    /// of information code, so that it can be decoded on its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `strips` - The data of each strip, in order.
+    /// * `into` - The output where compressed data should be written.
+    /// * `strip_offsets` - Cleared, then filled with the offset where each compressed strip
+    ///   starts, relative to the first byte written to `into`. Handy to fill the
+    ///   `StripOffsets` tag.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error].
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::encoder::{EncodingError, TiffStyleEncoder};
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let strips = [[0, 0, 1, 3], [0, 0, 1, 3]];
+    ///     let mut output = vec![];
+    ///     let mut offsets = vec![];
+    ///
+    ///     TiffStyleEncoder::encode_strips(
+    ///         strips.iter().map(|strip| &strip[..]),
+    ///         &mut output,
+    ///         &mut offsets,
+    ///     )?;
+    ///
+    ///     assert_eq!(offsets, [0, 7]);
+    ///     assert_eq!(&output[7..], [0x80, 0x0, 0x0, 0x0, 0x10, 0x1c, 0x4]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_strips<R: Read, W: Write, S: IntoIterator<Item = R>>(
+        strips: S,
+        into: W,
+        strip_offsets: &mut Vec<usize>,
+    ) -> Result<(), EncodingError> {
+        strip_offsets.clear();
+        let mut into = CountingWriter::new(into);
+        // Every strip starts over from an empty dictionary, but its allocation is kept.
+        let mut state = VariableEncoderState::new();
+
+        for strip in strips {
+            strip_offsets.push(into.bytes_written_usize()?);
+            let options = EncodingOptions {
+                code_size: TIFF_CODE_SIZE,
+                endianness: Endianness::BigEndian,
+                code_size_strategy: CodeSizeStrategy::Tiff,
+                ..Default::default()
+            };
+            state.encode_reusing(strip, &mut into, options)?;
+        }
+
+        Ok(())
+    }
+
+    /// Encode lzw, with variable code size, using the TIFF style, after applying the
+    /// horizontal differencing predictor (TIFF `Predictor` 2) to 8 bit samples.
+    ///
+    /// Each sample is replaced by its difference with the same component of the previous
+    /// pixel in the row, which makes smooth images compress a lot better.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed, rows of pixels one after the other.
+    /// * `into` - The output where compressed data should be written.
+    /// * `row_stride` - The size of a row, in bytes. The differencing starts over on each row.
+    /// * `samples_per_pixel` - How many samples make a pixel, 3 for RGB for example.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error], or if the row stride or the samples per
+    /// pixel are 0.
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::encoder::{EncodingError, TiffStyleEncoder};
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let gradient = [10, 11, 12, 13];
+    ///     let mut output = vec![];
+    ///
+    ///     TiffStyleEncoder::encode_with_predictor(&gradient[..], &mut output, 4, 1)?;
+    ///
+    ///     let differences = [10, 1, 1, 1];
+    ///     assert_eq!(output, TiffStyleEncoder::encode_to_vec(&differences[..])?);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_with_predictor<R: Read, W: Write>(
+        data: R,
+        into: W,
+        row_stride: usize,
+        samples_per_pixel: usize,
+    ) -> Result<(), EncodingError> {
+        let data = DifferencingReader::new(data, row_stride, samples_per_pixel)?;
+        TiffStyleEncoder::encode(data, into)
+    }
+}
+
+/// LZW encoder for the PDF `LZWDecode` filter.
+///
+/// Like TIFF, it starts at a write size of 9 bits and uses big endian packing, with a clear
+/// code of 256 and an end of data code of 257. The `EarlyChange` parameter of the filter
+/// decides whether the write size increases one code early, like TIFF, or not, like GIF.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PdfLzwEncoder;
+
+impl PdfLzwEncoder {
+    /// Encode lzw, to be read by the PDF `LZWDecode` filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The output where compressed data should be written.
+    /// * `early_change` - The `EarlyChange` parameter of the filter. When `true`, PDF's default,
+    ///   the write size increases one code early.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error].
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::encoder::{EncodingError, PdfLzwEncoder};
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![];
+    ///
+    ///     PdfLzwEncoder::encode(&data[..], &mut output, true)?;
+    ///
+    ///     assert_eq!(output, [0x80, 0x0, 0x0, 0x0, 0x10, 0x1c, 0x4]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode<R: Read, W: Write>(
+        data: R,
+        into: W,
+        early_change: bool,
+    ) -> Result<(), EncodingError> {
+        let code_size_strategy = if early_change {
+            CodeSizeStrategy::Tiff
+        } else {
+            CodeSizeStrategy::Default
+        };
+        VariableEncoder::inner_encode(
+            data,
+            BigEndianWriter::buffered(into),
+            PDF_CODE_SIZE,
+            MAX_CODE_SIZE,
+            code_size_strategy,
+            true,
+            DictionaryPolicy::Reset,
+        )
+    }
+
+    /// Encode lzw, to be read by the PDF `LZWDecode` filter.
+    /// Convenient wrapper that creates a [Vec<u8>] under the hood.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `early_change` - The `EarlyChange` parameter of the filter. When `true`, PDF's default,
+    ///   the write size increases one code early.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error].
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::encoder::{EncodingError, PdfLzwEncoder};
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///
+    ///     let output = PdfLzwEncoder::encode_to_vec(&data[..], true)?;
+    ///
+    ///     assert_eq!(output, [0x80, 0x0, 0x0, 0x0, 0x10, 0x1c, 0x4]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_to_vec<R: Read>(data: R, early_change: bool) -> Result<Vec<u8>, EncodingError> {
+        let mut data = data;
+        let mut output = encoded_output(&mut data);
+        PdfLzwEncoder::encode(data, &mut output, early_change)?;
+        Ok(output)
+    }
+}
+
+/// LZW encoder writing fixed 12 bit codes.
+///
+/// There is no clear or end of information codes: As soon as the dictionary is full,
+/// we stop increasing its size.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixedEncoder;
+
+impl FixedEncoder {
+    /// Compress data with LZW outputting fixed code of 12 bits.
+    ///
+    /// For really small amount data like the one showcased in the example, the compressed might
+    /// take more space than the original data. Variable code size compression is generally better.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The output where compressed data should be written.
+    /// * `endianness` - Bit ordering when writing compressed data.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error].
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::{
+    ///     encoder::{EncodingError, FixedEncoder},
+    ///     CodeSizeStrategy, Endianness,
+    /// };
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![];
+    ///
+    ///     FixedEncoder::encode(&data[..], &mut output, Endianness::LittleEndian)?;
+    ///
+    ///     assert_eq!(output, [0x0, 0x0, 0x0, 0x1, 0x30, 0x0]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode<R: Read, W: Write>(
+        data: R,
+        into: W,
+        endianness: Endianness,
+    ) -> Result<(), EncodingError> {
+        FixedEncoder::encode_with_bits(data, into, endianness, MAX_CODE_SIZE)
+    }
+
+    /// Compress data with LZW outputting fixed code of any size between 9 and 16 bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The output where compressed data should be written.
+    /// * `endianness` - Bit ordering when writing compressed data.
+    /// * `bits` - Between 9 and 16, the size of every code. The dictionary holds up to
+    ///   2.pow(bits) entries, then stops growing. A size of 12 is the same as
+    ///   [FixedEncoder::encode].
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error], or with [EncodingError::MaxCodeSize] for
+    /// an unsupported code size.
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::{
+    ///     encoder::{EncodingError, FixedEncoder},
+    ///     Endianness,
+    /// };
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![];
+    ///
+    ///     FixedEncoder::encode_with_bits(&data[..], &mut output, Endianness::LittleEndian, 16)?;
+    ///
+    ///     assert_eq!(output, [0x0, 0x0, 0x0, 0x0, 0x1, 0x0, 0x3, 0x0]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_with_bits<R: Read, W: Write>(
+        data: R,
+        into: W,
+        endianness: Endianness,
+        bits: u8,
+    ) -> Result<(), EncodingError> {
+        if !(9..=16).contains(&bits) {
+            return Err(EncodingError::MaxCodeSize(bits));
+        }
+
+        match endianness {
+            Endianness::BigEndian => {
+                FixedEncoder::inner_encode(data, BigEndianWriter::buffered(into), bits)
+            }
+            Endianness::LittleEndian => {
+                FixedEncoder::inner_encode(data, LittleEndianWriter::buffered(into), bits)
+            }
+        }
+    }
+
+    /// Compress data with LZW outputting fixed code of 12 bits.
+    /// Convenient wrapper that creates a [Vec<u8>] under the hood.
+    ///
+    /// For really small amount data like the one showcased in the example, the compressed might
+    /// take more space than the original data. Variable code size compression is generally better.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `endianness` - Bit ordering when writing compressed data.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error].
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::{
+    ///     encoder::{EncodingError, FixedEncoder},
+    ///     CodeSizeStrategy, Endianness,
+    /// };
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///
+    ///     let output = FixedEncoder::encode_to_vec(&data[..], Endianness::LittleEndian)?;
+    ///
+    ///     assert_eq!(output, [0x0, 0x0, 0x0, 0x1, 0x30, 0x0]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_to_vec<R: Read>(
+        data: R,
+        endianness: Endianness,
+    ) -> Result<Vec<u8>, EncodingError> {
+        let mut data = data;
+        let mut output = encoded_output(&mut data);
+        FixedEncoder::encode(data, &mut output, endianness)?;
+        Ok(output)
+    }
+
+    /// Compress data with LZW outputting fixed code of 12 bits.
+    /// Reuses an existing [Vec<u8>]: it is cleared first, keeping its capacity.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The vector where compressed data will be written, after clearing it.
+    /// * `endianness` - Bit ordering when writing compressed data.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error].
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::{
+    ///     encoder::{EncodingError, FixedEncoder},
+    ///     Endianness,
+    /// };
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![42; 8];
+    ///
+    ///     FixedEncoder::encode_into_vec(&data[..], &mut output, Endianness::LittleEndian)?;
+    ///
+    ///     assert_eq!(output, [0x0, 0x0, 0x0, 0x1, 0x30, 0x0]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_into_vec<R: Read>(
+        data: R,
+        into: &mut Vec<u8>,
+        endianness: Endianness,
+    ) -> Result<(), EncodingError> {
+        into.clear();
+        FixedEncoder::encode(data, into, endianness)
+    }
+
+    /// Compress data with LZW outputting fixed code of 12 bits.
+    /// Same as [FixedEncoder::encode], but returns the number of bytes written.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The output where compressed data should be written.
+    /// * `endianness` - Bit ordering when writing compressed data.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error].
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::{
+    ///     encoder::{EncodingError, FixedEncoder},
+    ///     Endianness,
+    /// };
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![];
+    ///
+    ///     let written =
+    ///         FixedEncoder::encode_counted(&data[..], &mut output, Endianness::LittleEndian)?;
+    ///
+    ///     assert_eq!(written, 6);
+    ///     assert_eq!(output, [0x0, 0x0, 0x0, 0x1, 0x30, 0x0]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_counted<R: Read, W: Write>(
+        data: R,
+        into: W,
+        endianness: Endianness,
+    ) -> Result<usize, EncodingError> {
+        let mut into = CountingWriter::new(into);
+        FixedEncoder::encode(data, &mut into, endianness)?;
+        Ok(into.bytes_written_usize()?)
+    }
+
+    /// The write size is expected to be already validated, between 9 and 16.
+    fn inner_encode<R: Read, B: BitWriter>(
+        data: R,
+        bit_writer: B,
+        write_size: u8,
+    ) -> Result<(), EncodingError> {
+        let mut bit_writer = bit_writer;
+        let mut state = FixedState::new(write_size);
+
+        let mut data = data;
+        let mut buffer = [0; READ_BUFFER_SIZE];
+        loop {
+            let read = read_chunk(&mut data, &mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            for &k in &buffer[..read] {
+                state.push(k, &mut bit_writer)?;
+            }
+        }
+
+        state.finish(&mut bit_writer)?;
+
+        bit_writer.fill()?;
+        bit_writer.flush()?;
+
+        Ok(())
+    }
+}
+
+/// The dictionary of a fixed code size encoder.
+///
+/// There is no clear code: once the dictionary is full, it stops growing and
+/// the existing words keep being used.
+pub(crate) struct FixedState {
+    tree: Tree,
+    write_size: u8,
+    max_table_size: usize,
+    current_prefix: Option<u16>,
+}
+
+impl FixedState {
+    /// The write size is expected to be already validated, between 9 and 16.
+    pub(crate) fn new(write_size: u8) -> Self {
+        let mut tree = Tree::new(8, 0);
+        tree.reset();
+
+        Self {
+            tree,
+            write_size,
+            max_table_size: 1 << write_size,
+            current_prefix: None,
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn push<B: BitWriter>(
+        &mut self,
+        k: u8,
+        bit_writer: &mut B,
+    ) -> Result<(), io::Error> {
+        let current_prefix = match self.current_prefix {
+            Some(current_prefix) => current_prefix,
+            None => {
+                // Very first byte of the stream, nothing to look up yet.
+                self.current_prefix = Some(k as u16);
+                return Ok(());
+            }
+        };
+
+        if let Some(word) = self.tree.find_word(current_prefix, k.into()) {
+            self.current_prefix = Some(word);
+        } else {
+            if self.tree.len() < self.max_table_size {
+                self.tree.add(current_prefix, k.into());
+            }
+            bit_writer.write(current_prefix, self.write_size)?;
+            self.current_prefix = Some(k as u16);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn finish<B: BitWriter>(&mut self, bit_writer: &mut B) -> Result<(), io::Error> {
+        if let Some(current_prefix) = self.current_prefix.take() {
+            bit_writer.write(current_prefix, self.write_size)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// LZW encoder for the Unix `compress` format, producing `.Z` files.
+///
+/// The output starts with a 3 bytes header: the magic bytes `0x1F 0x9D`, then a flag byte
+/// giving the maximum code size and enabling the block mode. Codes are written in little endian
+/// order, starting at 9 bits and growing up to the maximum code size. When the dictionary is
+/// full, a clear code (256) is emitted and the dictionary starts over.
+///
+/// The format has no end of information code: the stream simply ends with the data.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnixCompressEncoder;
+
+impl UnixCompressEncoder {
+    /// Compress data in the Unix `compress` format.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `into` - The output where compressed data should be written.
+    /// * `max_code_size` - Between 9 and 16, the maximum size of the written codes.
+    ///   The `compress` tool defaults to 16.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error] or for an unsupported maximum code size.
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::encoder::{EncodingError, UnixCompressEncoder};
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///     let mut output = vec![];
+    ///
+    ///     UnixCompressEncoder::encode(&data[..], &mut output, 16)?;
+    ///
+    ///     assert_eq!(output, [0x1f, 0x9d, 0x90, 0x0, 0x0, 0x4, 0x18, 0x0]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode<R: Read, W: Write>(
+        data: R,
+        into: W,
+        max_code_size: u8,
+    ) -> Result<(), EncodingError> {
+        const CLEAR_CODE: u16 = 256;
+        const FIRST_FREE_CODE: usize = 257;
+        const INITIAL_WRITE_SIZE: u8 = 9;
+
+        if !(INITIAL_WRITE_SIZE..=16).contains(&max_code_size) {
+            return Err(EncodingError::MaxCodeSize(max_code_size));
+        }
+
+        let mut into = into;
+        into.write_all(&UnixCompressEncoder::header(max_code_size))?;
+
+        let mut bit_writer = LittleEndianWriter::buffered(into);
+        let table_size = 1 << max_code_size;
+        let mut write_size = INITIAL_WRITE_SIZE;
+        let mut size_increase_mask = 1 << write_size;
+        // `compress` writes codes in groups of 8. When the code size changes, the rest
+        // of the current group is padded.
+        let mut codes_in_group: usize = 0;
+
+        let mut tree = Tree::new(8, FIRST_FREE_CODE - 256);
+        tree.reset();
+
+        let mut current_prefix: Option<u16> = None;
+
+        let mut data = data;
+        let mut buffer = [0; READ_BUFFER_SIZE];
+        loop {
+            let read = read_chunk(&mut data, &mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            for &k in &buffer[..read] {
+                let prefix = match current_prefix {
+                    Some(prefix) => prefix,
+                    None => {
+                        current_prefix = Some(k as u16);
+                        continue;
+                    }
+                };
+
+                if let Some(word) = tree.find_word(prefix, k.into()) {
+                    current_prefix = Some(word);
+                    continue;
+                }
+
+                let index_of_new_entry = tree.add(prefix, k.into());
+                bit_writer.write(prefix, write_size)?;
+                codes_in_group += 1;
+                current_prefix = Some(k as u16);
+
+                if index_of_new_entry == size_increase_mask && write_size < max_code_size {
+                    UnixCompressEncoder::pad_group(&mut bit_writer, codes_in_group, write_size)?;
+                    codes_in_group = 0;
+                    write_size += 1;
+                    size_increase_mask = 1 << write_size;
+                } else if tree.len() == table_size {
+                    bit_writer.write(CLEAR_CODE, write_size)?;
+                    codes_in_group += 1;
+                    UnixCompressEncoder::pad_group(&mut bit_writer, codes_in_group, write_size)?;
+                    codes_in_group = 0;
+                    write_size = INITIAL_WRITE_SIZE;
+                    size_increase_mask = 1 << write_size;
+                    tree.reset();
+                }
+            }
+        }
+
+        if let Some(prefix) = current_prefix {
+            bit_writer.write(prefix, write_size)?;
+        }
+
+        bit_writer.fill()?;
+        bit_writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Compress data in the Unix `compress` format.
+    /// Convenient wrapper that creates a [Vec<u8>] under the hood.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The source data to be compressed.
+    /// * `max_code_size` - Between 9 and 16, the maximum size of the written codes.
+    ///   The `compress` tool defaults to 16.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error] or for an unsupported maximum code size.
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::encoder::{EncodingError, UnixCompressEncoder};
+    ///
+    /// fn main() -> Result<(), EncodingError> {
+    ///     let data = [0, 0, 1, 3];
+    ///
+    ///     let output = UnixCompressEncoder::encode_to_vec(&data[..], 16)?;
+    ///
+    ///     assert_eq!(output, [0x1f, 0x9d, 0x90, 0x0, 0x0, 0x4, 0x18, 0x0]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn encode_to_vec<R: Read>(data: R, max_code_size: u8) -> Result<Vec<u8>, EncodingError> {
+        let mut data = data;
+        let mut output = encoded_output(&mut data);
+        UnixCompressEncoder::encode(data, &mut output, max_code_size)?;
+        Ok(output)
+    }
+
+    fn header(max_code_size: u8) -> [u8; 3] {
+        const BLOCK_MODE: u8 = 0x80;
+        [0x1f, 0x9d, BLOCK_MODE | max_code_size]
+    }
+
+    fn pad_group<B: BitWriter>(
+        bit_writer: &mut B,
+        codes_in_group: usize,
+        write_size: u8,
+    ) -> Result<(), io::Error> {
+        let padding = (8 - codes_in_group % 8) % 8;
+        for _ in 0..padding {
+            bit_writer.write(0, write_size)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::{BitReader, LittleEndianReader};
+
+    #[test]
+    fn encode_codes_of_4color_data() -> Result<(), EncodingError> {
+        let data = [
+            1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 2, 2, 2, 2,
+            2, 1, 1, 1, 0, 0, 0, 0, 2, 2, 2,
+        ];
+
+        let codes = VariableEncoder::encode_codes(&data[..], 2, CodeSizeStrategy::Default)?;
+
+        assert_eq!(
+            codes,
+            [4, 1, 6, 6, 2, 9, 9, 7, 8, 10, 2, 12, 1, 14, 15, 6, 0, 21, 0, 10, 5]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn encode_codes_with_clear_codes() -> Result<(), EncodingError> {
+        // Enough varied data to fill the dictionary more than once.
+        let mut seed: u32 = 42;
+        let data: Vec<u8> = (0..100_000)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (seed >> 16) as u8
+            })
+            .collect();
+
+        let codes = VariableEncoder::encode_codes(&data[..], 8, CodeSizeStrategy::Default)?;
+
+        assert_eq!(codes.first(), Some(&256));
+        assert_eq!(codes.last(), Some(&257));
+        assert!(codes.iter().all(|&code| code < 4096));
+        assert!(codes.iter().filter(|&&code| code == 256).count() > 1);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn error_source_is_the_io_error() {
+        use std::error::Error;
+
+        let error = EncodingError::from(io::Error::new(io::ErrorKind::WriteZero, "full"));
+        let source = error.source().unwrap();
+        assert_eq!(source.to_string(), "full");
+        assert!(source.downcast_ref::<io::Error>().is_some());
+
+        assert!(EncodingError::CodeSize(9).source().is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn encode_to_vec_caps_the_reserved_output() -> Result<(), EncodingError> {
+        let data = vec![0; 2 * io::MAX_RESERVED_OUTPUT];
+
+        let output = GifStyleEncoder::encode_to_vec(&data[..], 8)?;
+
+        // Runs compress well, the output never needed to grow past what was reserved.
+        assert!(output.len() < io::MAX_RESERVED_OUTPUT);
+        assert_eq!(output.capacity(), io::MAX_RESERVED_OUTPUT);
+        Ok(())
+    }
+
+    #[test]
+    fn encoder_builder_presets() -> Result<(), EncodingError> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+
+        let gif = EncoderBuilder::gif(CodeSize::new(7)?)
+            .build()?
+            .encode_to_vec(&data[..])?;
+        assert_eq!(
+            gif,
+            VariableEncoder::encode_to_vec(
+                &data[..],
+                7,
+                12,
+                Endianness::LittleEndian,
+                CodeSizeStrategy::Default
+            )?
+        );
+
+        let tiff = EncoderBuilder::tiff().build()?.encode_to_vec(&data[..])?;
+        assert_eq!(
+            tiff,
+            VariableEncoder::encode_to_vec(
+                &data[..],
+                8,
+                12,
+                Endianness::BigEndian,
+                CodeSizeStrategy::Tiff
+            )?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn encoder_builder_all_options() -> Result<(), EncodingError> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+
+        let built = EncoderBuilder::new()
+            .code_size(CodeSize::new(7)?)
+            .max_code_size(9)
+            .endianness(Endianness::BigEndian)
+            .strategy(CodeSizeStrategy::Tiff)
+            .leading_clear_code(false)
+            .dictionary_policy(DictionaryPolicy::Freeze)
+            .possibilities(121)
+            .build()?
+            .encode_to_vec(&data[..])?;
+        let mut with_options = vec![];
+        VariableEncoder::encode_with_options(
+            &data[..],
+            &mut with_options,
+            EncodingOptions {
+                code_size: 7,
+                max_code_size: 9,
+                endianness: Endianness::BigEndian,
+                code_size_strategy: CodeSizeStrategy::Tiff,
+                leading_clear_code: false,
+                dictionary_policy: DictionaryPolicy::Freeze,
+                possibilities: Some(121),
+                dictionary: None,
+                flush: true,
+            },
+        )?;
+
+        assert_eq!(built, with_options);
+        Ok(())
+    }
+
+    #[test]
+    fn code_size_round_trip() -> Result<(), EncodingError> {
+        use crate::{decoder::DecoderBuilder, CodeSize};
+
+        for invalid in [0, 9, 12, u8::MAX] {
+            assert_eq!(
+                CodeSize::new(invalid),
+                Err(EncodingError::CodeSize(invalid))
+            );
+            assert!(CodeSize::try_from(invalid).is_err());
+        }
+
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        for code_size in [7, 8] {
+            let checked = CodeSize::new(code_size)?;
+            assert_eq!(u8::from(checked), code_size);
+
+            let mut compressed = vec![];
+            EncoderBuilder::gif(checked)
+                .build()?
+                .encode(&data[..], &mut compressed)?;
+            assert_eq!(
+                compressed,
+                GifStyleEncoder::encode_to_vec(&data[..], code_size)?
+            );
+
+            let mut decompressed = vec![];
+            DecoderBuilder::new()
+                .code_size(checked)
+                .decode(&compressed[..], &mut decompressed)
+                .unwrap();
+            assert_eq!(decompressed, data);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn code_size_bounds_match_encoders_and_decoders() {
+        use crate::{
+            decoder::{DecoderBuilder, DecodingError, GifStyleDecoder},
+            CodeSize,
+        };
+
+        // Symbols 0 and 1 fit any code size.
+        let data = [0, 1, 1, 0, 1];
+        for code_size in [1, 2, 8] {
+            assert_eq!(CodeSize::try_from(code_size).map(u8::from), Ok(code_size));
+            assert_eq!(
+                CodeSize::for_decoding(code_size).map(u8::from),
+                Ok(code_size)
+            );
+
+            let compressed = EncoderBuilder::gif(CodeSize::new(code_size).unwrap())
+                .build()
+                .and_then(|encoder| encoder.encode_to_vec(&data[..]))
+                .unwrap();
+            let decompressed = DecoderBuilder::gif(CodeSize::for_decoding(code_size).unwrap())
+                .decode_to_vec(&compressed[..])
+                .unwrap();
+            assert_eq!(decompressed, data, "Code size {code_size}");
+        }
+
+        assert_eq!(CodeSize::try_from(9), Err(EncodingError::CodeSize(9)));
+        assert_eq!(CodeSize::for_decoding(9), Err(DecodingError::CodeSize(9)));
+
+        // The GIF presets stick to what GIF files use.
+        for code_size in [1, 9] {
+            assert_eq!(
+                GifStyleEncoder::encode_to_vec(&data[..], code_size),
+                Err(EncodingError::CodeSize(code_size))
+            );
+            assert_eq!(
+                GifStyleDecoder::decode_to_vec(&[0x0][..], code_size),
+                Err(DecodingError::CodeSize(code_size))
+            );
+            assert_eq!(
+                GifStyleDecoder::decode_iter(&[0x0][..], code_size).collect::<Vec<_>>(),
+                [Err(DecodingError::CodeSize(code_size))]
+            );
+            assert_eq!(
+                GifStyleDecoder::decode_bounded(&[0x0][..], vec![], code_size, 16),
+                Err(DecodingError::CodeSize(code_size))
+            );
+            assert_eq!(
+                Encoder::gif(code_size).err(),
+                Some(EncodingError::CodeSize(code_size))
+            );
+        }
+    }
+
+    #[test]
+    fn encoder_builder_checks_code_sizes() {
+        // The builder only takes checked code sizes.
+        assert_eq!(CodeSize::new(9).err(), Some(EncodingError::CodeSize(9)));
+
+        let result = EncoderBuilder::gif(CodeSize::new(8).unwrap())
+            .max_code_size(8)
+            .build();
+        assert_eq!(result.err(), Some(EncodingError::MaxCodeSize(8)));
+
+        let result = EncoderBuilder::gif(CodeSize::new(2).unwrap())
+            .possibilities(5)
+            .build();
+        assert_eq!(result.err(), Some(EncodingError::Possibilities(5)));
+
+        let result = EncoderBuilder::gif(CodeSize::new(2).unwrap())
+            .possibilities(1)
+            .build();
+        assert_eq!(result.err(), Some(EncodingError::Possibilities(1)));
+    }
+
+    #[test]
+    fn encode_from_iter_matches_encode() -> Result<(), EncodingError> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+
+        let mut from_iter = vec![];
+        VariableEncoder::encode_from_iter(
+            data.iter().copied(),
+            &mut from_iter,
+            7,
+            Endianness::LittleEndian,
+            CodeSizeStrategy::Default,
+        )?;
+        assert_eq!(from_iter, GifStyleEncoder::encode_to_vec(&data[..], 7)?);
+
+        let mut from_iter = vec![];
+        VariableEncoder::encode_from_iter(
+            data.iter().copied(),
+            &mut from_iter,
+            8,
+            Endianness::BigEndian,
+            CodeSizeStrategy::Tiff,
+        )?;
+        assert_eq!(from_iter, TiffStyleEncoder::encode_to_vec(&data[..])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn encode_symbols_round_trip() -> Result<(), EncodingError> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        // Pairs of bytes, as 10 bit symbols, enough to fill the dictionary a few times.
+        let symbols = data
+            .chunks(2)
+            .map(|pair| {
+                pair.iter()
+                    .fold(0, |symbol, &byte| symbol * 32 + byte as u16 % 32)
+            })
+            .collect::<Vec<_>>();
+
+        for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+            for strategy in [CodeSizeStrategy::Default, CodeSizeStrategy::Tiff] {
+                let mut compressed = vec![];
+                VariableEncoder::encode_symbols(
+                    &symbols,
+                    &mut compressed,
+                    10,
+                    12,
+                    endianness,
+                    strategy,
+                )?;
+
+                let decoded = crate::decoder::VariableDecoder::decode_symbols(
+                    &compressed[..],
+                    10,
+                    12,
+                    endianness,
+                    strategy,
+                )
+                .unwrap();
+                assert_eq!(decoded, symbols);
+            }
+        }
+
+        // Symbols of a byte encode like the bytes.
+        let bytes = data.iter().map(|&byte| byte as u16).collect::<Vec<_>>();
+        let mut compressed = vec![];
+        VariableEncoder::encode_symbols(
+            &bytes,
+            &mut compressed,
+            8,
+            12,
+            Endianness::BigEndian,
+            CodeSizeStrategy::Tiff,
+        )?;
+        assert_eq!(compressed, TiffStyleEncoder::encode_to_vec(&data[..])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tree_finds_wide_symbols() {
+        let mut tree = Tree::new(10, 2);
+        tree.reset();
+
+        let children = [700, 3, 1023, 512, 4];
+        let indices = children
+            .iter()
+            .map(|&k| tree.add(42, k) as u16)
+            .collect::<Vec<_>>();
+
+        for (&k, &index) in children.iter().zip(&indices) {
+            assert_eq!(tree.find_word(42, k), Some(index));
+        }
+        assert_eq!(tree.find_word(42, 5), None);
+    }
+
+    #[test]
+    fn encode_symbols_errors() {
+        let encode = |symbols: &[u16], code_size, max_code_size| {
+            VariableEncoder::encode_symbols(
+                symbols,
+                vec![],
+                code_size,
+                max_code_size,
+                Endianness::LittleEndian,
+                CodeSizeStrategy::Default,
+            )
+        };
+
+        assert_eq!(
+            encode(&[0, 1024], 10, 12),
+            Err(EncodingError::UnexpectedCode {
+                code: 1024,
+                code_size: 10
+            })
+        );
+        assert_eq!(encode(&[0], 16, 16), Err(EncodingError::CodeSize(16)));
+        assert_eq!(encode(&[0], 12, 12), Err(EncodingError::MaxCodeSize(12)));
+        assert_eq!(encode(&[32767, 0], 15, 16), Ok(()));
+    }
+
+    #[test]
+    fn encode_from_iter_unexpected_code() {
+        let result = VariableEncoder::encode_from_iter(
+            [0, 1, 4],
+            vec![],
+            2,
+            Endianness::LittleEndian,
+            CodeSizeStrategy::Default,
+        );
+
+        assert_eq!(
+            result.err(),
+            Some(EncodingError::UnexpectedCode {
+                code: 4,
+                code_size: 2
+            })
+        );
+    }
+
+    #[test]
+    fn encode_with_bits_unsupported() {
+        for bits in [8, 17] {
+            let result =
+                FixedEncoder::encode_with_bits(&[0u8][..], vec![], Endianness::LittleEndian, bits);
+
+            assert_eq!(result.err(), Some(EncodingError::MaxCodeSize(bits)));
+        }
+    }
+
+    #[test]
+    fn encode_with_stats_counts_clear_codes() -> Result<(), EncodingError> {
+        let data: Vec<u8> = (0..50_000u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8)
+            .collect();
+        let options = || EncodingOptions {
+            code_size: 8,
+            ..Default::default()
+        };
+
+        let mut output = vec![];
+        let stats = VariableEncoder::encode_with_stats(&data[..], &mut output, options())?;
+
+        let mut expected = vec![];
+        VariableEncoder::encode_with_options(&data[..], &mut expected, options())?;
+        assert_eq!(output, expected);
+        assert_eq!(stats.bytes_written, output.len());
+
+        // Noisy data keeps filling the dictionary, each fill growing the write size from 9 to
+        // 12 bits, then ending with a clear code.
+        assert!(stats.clear_codes > 1);
+        assert!(stats.code_size_increases >= 3 * (stats.clear_codes - 1));
+        assert!(stats.codes_emitted > stats.clear_codes);
+
+        let decoded = crate::decoder::GifStyleDecoder::decode_to_vec(&output[..], 8).unwrap();
+        assert_eq!(decoded, data);
+
+        Ok(())
+    }
+
+    #[test]
+    fn encode_capped_code_size_round_trip() -> Result<(), EncodingError> {
+        let data = include_bytes!("../../test-assets/sunflower.bmp");
+
+        for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+            for code_size_strategy in [CodeSizeStrategy::Default, CodeSizeStrategy::Tiff] {
+                let options = EncodingOptions {
+                    code_size: 8,
+                    max_code_size: 10,
+                    endianness,
+                    code_size_strategy,
+                    ..Default::default()
+                };
+                let mut output = vec![];
+                let stats = VariableEncoder::encode_with_stats(&data[..], &mut output, options)?;
+
+                // Each fill only grows the write size from 9 to 10 bits before the clear code.
+                assert!(stats.clear_codes > 1);
+                assert!(stats.code_size_increases <= stats.clear_codes + 1);
+
+                let decoded = crate::decoder::VariableDecoder::decode_to_vec(
+                    &output[..],
+                    8,
+                    10,
+                    endianness,
+                    code_size_strategy,
+                )
+                .unwrap();
+                assert_eq!(decoded, data);
+
+                // A decoder not told about the cap keeps growing, and loses track of the codes.
+                let uncapped = crate::decoder::VariableDecoder::decode_to_vec(
+                    &output[..],
+                    8,
+                    12,
+                    endianness,
+                    code_size_strategy,
+                );
+                assert!(uncapped.map_or(true, |decoded| decoded != data));
             }
         }
+
+        Ok(())
     }
 
-    /// Compress data with LZW outputting fixed code of 12 bits.
-    /// Convenient wrapper that creates a [Vec<u8>] under the hood.
-    ///
-    /// For really small amount data like the one showcased in the example, the compressed might
-    /// take more space than the original data. Variable code size compression is generally better.
-    ///
-    /// # Arguments
-    ///
-    /// * `data` - The source data to be compressed.
-    /// * `endianness` - Bit ordering when writing compressed data.
-    ///
-    /// # Errors
-    ///
-    /// This function can fail on an [std::io::Error].
-    ///
-    /// # Examples
-    /// ```
-    /// use salzweg::{
-    ///     encoder::{EncodingError, FixedEncoder},
-    ///     CodeSizeStrategy, Endianness,
-    /// };
-    ///
-    /// fn main() -> Result<(), EncodingError> {
-    ///     let data = [0, 0, 1, 3];
-    ///
-    ///     let output = FixedEncoder::encode_to_vec(&data[..], Endianness::LittleEndian)?;
-    ///
-    ///     assert_eq!(output, [0x0, 0x0, 0x0, 0x1, 0x30, 0x0]);
-    ///     Ok(())
-    /// }
-    /// ```
-    pub fn encode_to_vec<R: Read>(
-        data: R,
-        endianness: Endianness,
-    ) -> Result<Vec<u8>, EncodingError> {
-        let mut output = vec![];
-        FixedEncoder::encode(data, &mut output, endianness)?;
-        Ok(output)
+    #[test]
+    fn encode_returning_hands_back_the_streams() -> Result<(), EncodingError> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        let header = b"LZW".to_vec();
+
+        let (rest, output) = VariableEncoder::encode_returning(
+            &data[..],
+            header.clone(),
+            7,
+            12,
+            Endianness::LittleEndian,
+            CodeSizeStrategy::Default,
+        )?;
+
+        assert!(rest.is_empty());
+        assert_eq!(output[..3], header);
+        assert_eq!(output[3..], GifStyleEncoder::encode_to_vec(&data[..], 7)?);
+
+        Ok(())
     }
 
-    fn inner_encode<R: Read, B: BitWriter>(data: R, bit_writer: B) -> Result<(), EncodingError> {
-        const WRITE_SIZE: u8 = 12;
-        const MAX_TABLE_SIZE: usize = 4096;
+    #[test]
+    fn encode_with_writer_matches_encode() -> Result<(), EncodingError> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
 
-        let mut bit_writer = bit_writer;
+        let mut writer = BigEndianWriter::new(vec![]);
+        VariableEncoder::encode_with_writer(&data[..], &mut writer, 8, 12, CodeSizeStrategy::Tiff)?;
 
-        let mut tree = Tree::new(8, false);
-        tree.reset();
+        assert_eq!(
+            writer.into_inner(),
+            TiffStyleEncoder::encode_to_vec(&data[..])?
+        );
 
-        let mut bytes = data.bytes();
-        let k = bytes.next();
-        if k.is_none() {
-            // Well, it's an empty stream! Leaving early.
-            bit_writer.fill()?;
-            bit_writer.flush()?;
+        Ok(())
+    }
 
-            return Ok(());
+    #[test]
+    fn estimate_size_matches_encode() -> Result<(), EncodingError> {
+        let lorem_ipsum = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        let sunflower = include_bytes!("../../test-assets/sunflower.bmp");
+
+        for data in [&lorem_ipsum[..], &sunflower[..], &[]] {
+            for code_size_strategy in [CodeSizeStrategy::Default, CodeSizeStrategy::Tiff] {
+                let size = VariableEncoder::estimate_size(data, 8, 12, code_size_strategy)?;
+                let encoded = VariableEncoder::encode_to_vec(
+                    data,
+                    8,
+                    12,
+                    Endianness::BigEndian,
+                    code_size_strategy,
+                )?;
+
+                assert_eq!(size, encoded.len());
+            }
         }
 
-        let mut current_prefix = k.unwrap()? as u16;
+        assert_eq!(
+            VariableEncoder::estimate_size(&[4][..], 2, 12, CodeSizeStrategy::Default),
+            Err(EncodingError::UnexpectedCode {
+                code: 4,
+                code_size: 2
+            })
+        );
+
+        Ok(())
+    }
 
-        for k in bytes {
-            let k = k?;
+    #[test]
+    fn encode_tiff_early_change_round_trip() -> Result<(), EncodingError> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
 
-            if let Some(word) = tree.find_word(current_prefix, k) {
-                current_prefix = word;
-            } else {
-                if tree.len() < MAX_TABLE_SIZE {
-                    tree.add(current_prefix, k);
-                }
-                bit_writer.write(current_prefix, WRITE_SIZE)?;
-                current_prefix = k as u16;
-            }
+        for early_change in [false, true] {
+            let mut compressed = vec![];
+            TiffStyleEncoder::encode_with_early_change(&data[..], &mut compressed, early_change)?;
+
+            let mut decoded = vec![];
+            crate::decoder::TiffStyleDecoder::decode_with_early_change(
+                &compressed[..],
+                &mut decoded,
+                early_change,
+            )
+            .unwrap();
+            assert_eq!(decoded, data);
         }
 
-        bit_writer.write(current_prefix, WRITE_SIZE)?;
-        bit_writer.fill()?;
-        bit_writer.flush()?;
+        let mut compressed = vec![];
+        TiffStyleEncoder::encode_with_early_change(&data[..], &mut compressed, true)?;
+        assert_eq!(compressed, TiffStyleEncoder::encode_to_vec(&data[..])?);
 
         Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn encode_reusing_state() -> Result<(), EncodingError> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        let mut state = VariableEncoderState::new();
+
+        // Alternating settings, so that each encode starts from a dictionary left dirty.
+        let settings = [(7, 12), (8, 9), (7, 16), (8, 12), (8, 13)];
+        for (index, (code_size, max_code_size)) in settings.into_iter().enumerate() {
+            let buffer = &data[index * 1000..(index + 2) * 1000];
+            let options = || EncodingOptions {
+                code_size,
+                max_code_size,
+                endianness: if index % 2 == 0 {
+                    Endianness::LittleEndian
+                } else {
+                    Endianness::BigEndian
+                },
+                ..Default::default()
+            };
+
+            let mut reused = vec![];
+            state.encode_reusing(buffer, &mut reused, options())?;
+            let mut one_shot = vec![];
+            VariableEncoder::encode_with_options(buffer, &mut one_shot, options())?;
+
+            assert_eq!(reused, one_shot);
+        }
+
+        Ok(())
+    }
 
     #[test]
     fn encode_4color_data() {
@@ -674,6 +3967,7 @@ mod tests {
             &data[..],
             &mut compressed,
             2,
+            12,
             Endianness::LittleEndian,
             CodeSizeStrategy::Default,
         )
@@ -694,6 +3988,7 @@ mod tests {
             &data[..],
             &mut compressed,
             2,
+            12,
             Endianness::LittleEndian,
             CodeSizeStrategy::Default,
         )
@@ -721,6 +4016,7 @@ mod tests {
         let compression1 = VariableEncoder::encode_to_vec(
             &data[..],
             2,
+            12,
             Endianness::LittleEndian,
             CodeSizeStrategy::Default,
         )
@@ -728,6 +4024,7 @@ mod tests {
         let compression2 = VariableEncoder::encode_to_vec(
             &data[..],
             2,
+            12,
             Endianness::LittleEndian,
             CodeSizeStrategy::Default,
         )
@@ -746,6 +4043,7 @@ mod tests {
             &data[..],
             &mut compressed,
             7,
+            12,
             Endianness::LittleEndian,
             CodeSizeStrategy::Default,
         )
@@ -763,6 +4061,7 @@ mod tests {
             &data[..],
             into,
             10,
+            12,
             Endianness::LittleEndian,
             CodeSizeStrategy::Default,
         )
@@ -770,7 +4069,25 @@ mod tests {
         .unwrap();
         let expected = EncodingError::CodeSize(10);
 
-        assert_eq!(expected.to_string(), result.to_string());
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn unsupported_max_code_size() {
+        let data = [0];
+
+        let result = VariableEncoder::encode_to_vec(
+            &data[..],
+            8,
+            8,
+            Endianness::LittleEndian,
+            CodeSizeStrategy::Default,
+        )
+        .err()
+        .unwrap();
+        let expected = EncodingError::MaxCodeSize(8);
+
+        assert_eq!(expected, result);
     }
 
     #[test]
@@ -780,6 +4097,7 @@ mod tests {
         let result = VariableEncoder::encode_to_vec(
             &data[..],
             2,
+            12,
             Endianness::BigEndian,
             CodeSizeStrategy::Default,
         )
@@ -791,7 +4109,7 @@ mod tests {
         };
 
         println!("{expected}");
-        assert_eq!(expected.to_string(), result.to_string());
+        assert_eq!(expected, result);
     }
 
     #[test]
@@ -833,4 +4151,380 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn encode_chunks_split_at_every_offset() -> Result<(), EncodingError> {
+        let data = [
+            1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 2, 2, 2, 2,
+            2, 1, 1, 1, 0, 0, 0, 0, 2, 2, 2,
+        ];
+
+        let expected_gif = GifStyleEncoder::encode_to_vec(&data[..], 2)?;
+        let expected_tiff = TiffStyleEncoder::encode_to_vec(&data[..])?;
+
+        for offset in 0..=data.len() {
+            let (head, tail) = data.split_at(offset);
+
+            let mut compressed = vec![];
+            let mut encoder = Encoder::gif(2)?;
+            encoder.encode_chunk(head, &mut compressed)?;
+            encoder.encode_chunk(tail, &mut compressed)?;
+            encoder.finish(&mut compressed)?;
+            assert_eq!(compressed, expected_gif, "GIF split at {offset}");
+
+            let mut compressed = vec![];
+            let mut encoder = Encoder::tiff();
+            encoder.encode_chunk(head, &mut compressed)?;
+            encoder.encode_chunk(tail, &mut compressed)?;
+            encoder.finish(&mut compressed)?;
+            assert_eq!(compressed, expected_tiff, "TIFF split at {offset}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn encode_chunks_lorem_ipsum() -> Result<(), EncodingError> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        let expected = include_bytes!("../../test-assets/lorem_ipsum_encoded.bin");
+
+        for chunk_size in [1, 7, 255, 4096] {
+            let mut compressed = vec![];
+            let mut encoder = Encoder::gif(7)?;
+            for chunk in data.chunks(chunk_size) {
+                encoder.encode_chunk(chunk, &mut compressed)?;
+            }
+            encoder.finish(&mut compressed)?;
+
+            assert_eq!(compressed, expected, "Chunks of size {chunk_size}");
+        }
+
+        Ok(())
+    }
+
+    /// Keeps the bytes written, counting the calls to `flush`.
+    #[derive(Default)]
+    struct FlushCounter {
+        bytes: Vec<u8>,
+        flushes: usize,
+    }
+
+    impl Write for FlushCounter {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, io::Error> {
+            self.bytes.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), io::Error> {
+            self.flushes += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn encode_without_flushing() -> Result<(), EncodingError> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        let expected = include_bytes!("../../test-assets/lorem_ipsum_encoded.bin");
+
+        let mut output = FlushCounter::default();
+        EncoderBuilder::gif(CodeSize::new(7)?)
+            .build()?
+            .encode(&data[..], &mut output)?;
+        assert_eq!(output.bytes, expected);
+        assert!(output.flushes > 0);
+
+        let mut output = FlushCounter::default();
+        EncoderBuilder::gif(CodeSize::new(7)?)
+            .flush(false)
+            .build()?
+            .encode(&data[..], &mut output)?;
+        assert_eq!(output.bytes, expected);
+        assert_eq!(output.flushes, 0);
+
+        let mut output = FlushCounter::default();
+        let mut encoder = Encoder::gif(7)?;
+        encoder.encode_chunk(data, &mut output)?;
+        encoder.finish_no_flush(&mut output)?;
+        assert_eq!(output.bytes, expected);
+        assert_eq!(output.flushes, 0);
+
+        // More data follows the stream in the same buffer, only flushed once at the end.
+        #[cfg(feature = "std")]
+        {
+            let mut buffered = std::io::BufWriter::new(FlushCounter::default());
+            EncoderBuilder::gif(CodeSize::new(7)?)
+                .flush(false)
+                .build()?
+                .encode(&data[..], &mut buffered)?;
+            buffered.write_all(b"trailer")?;
+            assert_eq!(buffered.get_ref().flushes, 0);
+
+            buffered.flush()?;
+            let output = buffered.get_ref();
+            assert_eq!(output.bytes, [&expected[..], b"trailer"].concat());
+            assert_eq!(output.flushes, 1);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn unfinished_encoder_truncates_the_stream() -> Result<(), EncodingError> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+
+        let mut compressed = vec![];
+        let mut encoder = Encoder::gif(7)?;
+        encoder.encode_chunk(data, &mut compressed)?;
+        let unfinished = compressed.clone();
+        encoder.finish(&mut compressed)?;
+
+        assert_eq!(
+            crate::decoder::GifStyleDecoder::decode_to_vec(&compressed[..], 7).unwrap(),
+            data
+        );
+        assert!(crate::decoder::GifStyleDecoder::decode_to_vec(&unfinished[..], 7).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn cloned_encoder_forks_the_stream() -> Result<(), EncodingError> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        let (head, tail) = data.split_at(data.len() / 2);
+        let other_tail: Vec<u8> = tail.iter().rev().copied().collect();
+
+        let mut prefix = vec![];
+        let mut encoder = Encoder::gif(7)?;
+        encoder.encode_chunk(head, &mut prefix)?;
+        let mut fork = encoder.clone();
+
+        let mut compressed = prefix.clone();
+        encoder.encode_chunk(tail, &mut compressed)?;
+        encoder.finish(&mut compressed)?;
+
+        let mut forked = prefix;
+        fork.encode_chunk(&other_tail, &mut forked)?;
+        fork.finish(&mut forked)?;
+
+        assert_eq!(compressed, GifStyleEncoder::encode_to_vec(&data[..], 7)?);
+        let expected: Vec<u8> = head.iter().chain(&other_tail).copied().collect();
+        assert_eq!(forked, GifStyleEncoder::encode_to_vec(&expected[..], 7)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn checkpoints_round_trip() -> Result<(), EncodingError> {
+        let mut seed: u32 = 7;
+        let noise: Vec<u8> = (0..3_000)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (seed >> 16) as u8
+            })
+            .collect();
+
+        for (code_size, endianness, code_size_strategy) in [
+            (1, Endianness::BigEndian, CodeSizeStrategy::Tiff),
+            (2, Endianness::LittleEndian, CodeSizeStrategy::Default),
+            (4, Endianness::BigEndian, CodeSizeStrategy::Tiff),
+            (8, Endianness::LittleEndian, CodeSizeStrategy::Default),
+        ] {
+            let data: Vec<u8> = noise.iter().map(|k| k >> (8 - code_size)).collect();
+
+            // Checkpoints every few bytes, to hit the code size increases at every offset.
+            for interval in [1, 2, 3, 5, 7, 11, 64, 500] {
+                let mut compressed = vec![];
+                let mut encoder = Encoder::new(code_size, endianness, code_size_strategy)?;
+                for chunk in data.chunks(interval) {
+                    encoder.encode_chunk(chunk, &mut compressed)?;
+                    encoder.checkpoint(&mut compressed)?;
+                }
+                encoder.finish(&mut compressed)?;
+
+                let decoded = crate::decoder::VariableDecoder::decode_to_vec(
+                    &compressed[..],
+                    code_size,
+                    MAX_CODE_SIZE,
+                    endianness,
+                    code_size_strategy,
+                )
+                .unwrap();
+                assert_eq!(decoded, data, "code size {code_size}, interval {interval}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn push_runs_like_single_bytes() -> Result<(), EncodingError> {
+        // Runs of all lengths, long enough to fill the dictionary with run words, broken up
+        // by a few other bytes.
+        let mut data = vec![];
+        for length in 0..300 {
+            data.resize(data.len() + length, (length % 3) as u8);
+            data.extend([1, 2, 0, 3]);
+        }
+        data.resize(data.len() + 40_000, 2);
+
+        for code_size in [2, 8] {
+            for max_code_size in [code_size + 1, 9, 12] {
+                for code_size_strategy in [CodeSizeStrategy::Default, CodeSizeStrategy::Tiff] {
+                    for dictionary_policy in [DictionaryPolicy::Reset, DictionaryPolicy::Freeze] {
+                        let state = || {
+                            VariableState::new(
+                                code_size,
+                                max_code_size,
+                                code_size_strategy,
+                                dictionary_policy,
+                            )
+                        };
+
+                        let mut single = state();
+                        let mut single_writer = LittleEndianWriter::new(vec![]);
+                        for &k in &data {
+                            single.push(k, &mut single_writer)?;
+                        }
+                        single.finish(&mut single_writer)?;
+
+                        let mut runs = state();
+                        let mut runs_writer = LittleEndianWriter::new(vec![]);
+                        runs.push_slice(&data, &mut runs_writer)?;
+                        runs.finish(&mut runs_writer)?;
+
+                        assert!(
+                            runs_writer.into_inner() == single_writer.into_inner(),
+                            "code size {code_size}, max code size {max_code_size}, \
+                             {code_size_strategy:?}, {dictionary_policy:?}"
+                        );
+                        assert_eq!(runs.stats, single.stats);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn current_code_width_follows_the_strategy() -> Result<(), EncodingError> {
+        let data = [0, 0, 1, 3];
+
+        for (code_size_strategy, expected) in [
+            (CodeSizeStrategy::Default, [3, 3, 3, 4]),
+            (CodeSizeStrategy::Tiff, [3, 3, 4, 4]),
+        ] {
+            let mut encoder = Encoder::new(2, Endianness::LittleEndian, code_size_strategy)?;
+            assert_eq!(encoder.current_code_width(), 3);
+
+            let mut widths = vec![];
+            for k in data {
+                encoder.encode_chunk(&[k], vec![])?;
+                widths.push(encoder.current_code_width());
+            }
+            assert_eq!(widths, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn encode_chunks_empty() -> Result<(), EncodingError> {
+        let mut compressed = vec![];
+        let mut encoder = Encoder::gif(2)?;
+        encoder.encode_chunk(&[], &mut compressed)?;
+        encoder.finish(&mut compressed)?;
+
+        assert_eq!(compressed, GifStyleEncoder::encode_to_vec(&[][..], 2)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn encode_blocks_spanning_multiple_sub_blocks() -> Result<(), EncodingError> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        let expected = GifStyleEncoder::encode_to_vec(&data[..], 7)?;
+
+        let mut framed = vec![];
+        GifStyleEncoder::encode_blocks(&data[..], &mut framed, 7)?;
+
+        assert_eq!(framed[0], 7);
+
+        let mut compressed = vec![];
+        let mut blocks = &framed[1..];
+        let mut block_count = 0;
+        loop {
+            let length = blocks[0] as usize;
+            if length == 0 {
+                assert_eq!(blocks.len(), 1, "Nothing should follow the terminator");
+                break;
+            }
+            compressed.extend_from_slice(&blocks[1..=length]);
+            blocks = &blocks[length + 1..];
+            block_count += 1;
+
+            if compressed.len() < expected.len() {
+                assert_eq!(length, 255, "Only the last sub-block can be shorter");
+            }
+        }
+
+        assert_eq!(block_count, expected.len().div_ceil(255));
+        assert_eq!(compressed, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_max_code_size_unix_compress() {
+        let data = [0];
+
+        let result = UnixCompressEncoder::encode_to_vec(&data[..], 17)
+            .err()
+            .unwrap();
+        let expected = EncodingError::MaxCodeSize(17);
+
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn encode_counted_matches_output_length() -> Result<(), EncodingError> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+
+        let mut output = vec![];
+        let written = GifStyleEncoder::encode_counted(&data[..], &mut output, 7)?;
+        assert_eq!(written, output.len());
+
+        let mut output = vec![];
+        let written = TiffStyleEncoder::encode_counted(&data[..], &mut output)?;
+        assert_eq!(written, output.len());
+
+        let mut output = vec![];
+        let written = FixedEncoder::encode_counted(&data[..], &mut output, Endianness::BigEndian)?;
+        assert_eq!(written, output.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn encode_without_leading_clear_code() -> Result<(), EncodingError> {
+        let data = [0, 0, 1, 3];
+        let mut output = vec![];
+
+        let options = EncodingOptions {
+            code_size: 2,
+            leading_clear_code: false,
+            ..Default::default()
+        };
+        VariableEncoder::encode_with_options(&data[..], &mut output, options)?;
+
+        // Same codes as [0x04, 0x32, 0x05], minus the leading clear code.
+        let mut reader = LittleEndianReader::new(&output[..]);
+        let codes: Result<Vec<u16>, _> = [3, 3, 3, 4, 4]
+            .into_iter()
+            .map(|read_size| reader.read_one(read_size))
+            .collect();
+        assert_eq!(codes?, [0, 0, 1, 3, 5]);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,316 @@
+//! Helpers for the Image Data of GIF images.
+//!
+//! In a GIF file, the pixels of an image are color indices, compressed with the GIF style LZW
+//! and framed as the Image Data: the LZW minimum code size byte, then the compressed data
+//! chopped into sub-blocks, then the block terminator. These helpers go from the indices to the
+//! exact bytes of that section, and back, in one call or, with [ImageDataWriter], as the
+//! indices come.
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    decoder::{DecodingError, GifStyleDecoder},
+    encoder::{Encoder, EncodingError, GifStyleEncoder},
+    io::{self, SubBlockWriter, Write},
+};
+
+/// Compress the color indices of an image into the bytes of its GIF Image Data section.
+///
+/// # Arguments
+///
+/// * `indices` - The color index of each pixel, all below 2.pow(min_code_size).
+/// * `min_code_size` - Between 2 and 8, the LZW minimum code size. GIF uses 2 for palettes of
+///   2 or 4 colors, and the number of bits of the palette size for the bigger ones.
+///
+/// # Errors
+///
+/// This function fails for unsupported code sizes, or for indices too big for the code size.
+///
+/// # Examples
+/// ```
+/// use salzweg::{encoder::EncodingError, gif};
+///
+/// fn main() -> Result<(), EncodingError> {
+///     let indices = [0, 0, 1, 3];
+///
+///     let image_data = gif::compress_image_data(&indices, 2)?;
+///
+///     assert_eq!(image_data, [0x02, 0x03, 0x04, 0x32, 0x05, 0x00]);
+///     Ok(())
+/// }
+/// ```
+pub fn compress_image_data(indices: &[u8], min_code_size: u8) -> Result<Vec<u8>, EncodingError> {
+    let mut image_data = vec![];
+    GifStyleEncoder::encode_blocks(indices, &mut image_data, min_code_size)?;
+    Ok(image_data)
+}
+
+/// Decompress the bytes of a GIF Image Data section into the color indices of the image.
+///
+/// The data is read up to the block terminator: anything following it, like the next blocks
+/// of the GIF file, is ignored.
+///
+/// # Arguments
+///
+/// * `image_data` - The Image Data section, starting with the LZW minimum code size byte.
+///
+/// # Errors
+///
+/// This function fails for unexpected codes or code sizes, or with
+/// [DecodingError::TruncatedInput] if the data ends before the block terminator.
+///
+/// # Examples
+/// ```
+/// use salzweg::{decoder::DecodingError, gif};
+///
+/// fn main() -> Result<(), DecodingError> {
+///     let image_data = [0x02, 0x03, 0x04, 0x32, 0x05, 0x00];
+///
+///     let indices = gif::decompress_image_data(&image_data)?;
+///
+///     assert_eq!(indices, [0, 0, 1, 3]);
+///     Ok(())
+/// }
+/// ```
+pub fn decompress_image_data(image_data: &[u8]) -> Result<Vec<u8>, DecodingError> {
+    let mut indices = vec![];
+    GifStyleDecoder::decode_blocks(image_data, &mut indices)?;
+    Ok(indices)
+}
+
+/// Compresses color indices as they are written, into a GIF Image Data section.
+///
+/// The streaming counterpart of [compress_image_data]: the LZW minimum code size byte is
+/// written right away, then the compressed data goes to the inner writer one full sub-block
+/// of 255 bytes at a time, so the whole compressed image is never held in memory.
+///
+/// The section is only complete once [ImageDataWriter::finish] has been called, writing the
+/// pending codes, the last sub-block and the block terminator.
+///
+/// Indices already compressed can't be taken back: after an error of the inner writer, the
+/// section can't be completed, and any further call fails.
+///
+/// # Examples
+/// ```
+/// use salzweg::{encoder::EncodingError, gif::ImageDataWriter, io::Write};
+///
+/// fn main() -> Result<(), EncodingError> {
+///     let mut writer = ImageDataWriter::new(vec![], 2)?;
+///     writer.write_all(&[0, 0])?;
+///     writer.write_all(&[1, 3])?;
+///     let image_data = writer.finish()?;
+///
+///     assert_eq!(image_data, [0x02, 0x03, 0x04, 0x32, 0x05, 0x00]);
+///     Ok(())
+/// }
+/// ```
+#[must_use = "the Image Data is incomplete until `ImageDataWriter::finish` is called"]
+pub struct ImageDataWriter<W>
+where
+    W: Write,
+{
+    encoder: Encoder,
+    sub_blocks: SubBlockWriter<W>,
+    max_index: u8,
+    // Set once the inner writer failed, part of the written indices being already compressed.
+    failed: bool,
+}
+
+impl<W> ImageDataWriter<W>
+where
+    W: Write,
+{
+    /// Start an Image Data section, writing its LZW minimum code size byte to `into`.
+    ///
+    /// # Arguments
+    ///
+    /// * `into` - Where the Image Data section will be written.
+    /// * `min_code_size` - Between 2 and 8, the LZW minimum code size. All the written indices
+    ///   must be below 2.pow(min_code_size).
+    ///
+    /// # Errors
+    ///
+    /// This function fails for unsupported code sizes, or on an [io::Error].
+    pub fn new(into: W, min_code_size: u8) -> Result<Self, EncodingError> {
+        if !(2..=8).contains(&min_code_size) {
+            return Err(EncodingError::CodeSize(min_code_size));
+        }
+
+        let mut into = into;
+        into.write_all(&[min_code_size])?;
+
+        Ok(Self {
+            encoder: Encoder::gif(min_code_size)?,
+            sub_blocks: SubBlockWriter::new(into),
+            max_index: ((1u16 << min_code_size) - 1) as u8,
+            failed: false,
+        })
+    }
+
+    fn check_not_failed(&self) -> Result<(), io::Error> {
+        if self.failed {
+            Err(io::Error::other(
+                "The Image Data writer failed earlier, the section can't be completed",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Terminate the section: writes the pending codes, the last sub-block and the block
+    /// terminator, then hands back the inner writer.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail on an [io::Error], or if an earlier call failed.
+    pub fn finish(self) -> Result<W, EncodingError> {
+        self.check_not_failed()?;
+
+        let mut sub_blocks = self.sub_blocks;
+        self.encoder.finish(&mut sub_blocks)?;
+
+        Ok(sub_blocks.finish()?)
+    }
+}
+
+impl<W> Write for ImageDataWriter<W>
+where
+    W: Write,
+{
+    /// Compress the color indices of `buf`. They are all consumed, but only reach the inner
+    /// writer once a sub-block is full.
+    ///
+    /// Fails with [InvalidInput](io::ErrorKind::InvalidInput), consuming nothing, if any
+    /// index is too big for the LZW minimum code size. Once the inner writer failed, part of
+    /// `buf` is already compressed: this and any further call fail.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, io::Error> {
+        self.check_not_failed()?;
+        if buf.iter().any(|&index| index > self.max_index) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Color index too big for the LZW minimum code size",
+            ));
+        }
+
+        match self.encoder.encode_chunk(buf, &mut self.sub_blocks) {
+            Ok(()) => Ok(buf.len()),
+            Err(EncodingError::Io(error)) => {
+                self.failed = true;
+                Err(error)
+            }
+            // The indices are checked, only writing can fail.
+            Err(error) => unreachable!("{error}"),
+        }
+    }
+
+    /// Flushes the inner writer. The current sub-block stays open, and the last codes stay
+    /// with the encoder, as more indices could still extend them.
+    fn flush(&mut self) -> Result<(), io::Error> {
+        self.check_not_failed()?;
+        self.sub_blocks.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_data_round_trip() -> Result<(), DecodingError> {
+        // Big enough for many sub-blocks, followed by the GIF trailer.
+        let indices = include_bytes!("../../test-assets/sunflower.bmp");
+        let mut image_data = compress_image_data(&indices[..], 8).unwrap();
+        assert!(image_data.len() > 255);
+        image_data.push(0x3b);
+
+        assert_eq!(decompress_image_data(&image_data)?, indices);
+
+        assert_eq!(
+            decompress_image_data(&image_data[..image_data.len() - 2]),
+            Err(DecodingError::TruncatedInput)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn compress_unsupported_code_size() {
+        assert_eq!(
+            compress_image_data(&[0, 1], 1),
+            Err(EncodingError::CodeSize(1))
+        );
+        assert_eq!(
+            ImageDataWriter::new(vec![], 9).err(),
+            Some(EncodingError::CodeSize(9))
+        );
+    }
+
+    #[test]
+    fn image_data_writer_matches_compress_image_data() -> Result<(), EncodingError> {
+        let indices = include_bytes!("../../test-assets/sunflower.bmp");
+        let expected = compress_image_data(&indices[..], 8)?;
+
+        // Uneven chunks, some smaller and some bigger than a sub-block.
+        let mut writer = ImageDataWriter::new(vec![], 8)?;
+        for chunk in indices.chunks(1000).flat_map(|chunk| chunk.chunks(700)) {
+            writer.write_all(chunk)?;
+        }
+        let image_data = writer.finish()?;
+
+        assert_eq!(image_data, expected);
+        assert_eq!(decompress_image_data(&image_data).unwrap(), indices);
+
+        Ok(())
+    }
+
+    #[test]
+    fn image_data_writer_only_writes_full_sub_blocks() -> Result<(), EncodingError> {
+        let indices = include_bytes!("../../test-assets/sunflower.bmp");
+
+        let mut output = vec![];
+        let mut writer = ImageDataWriter::new(&mut output, 8)?;
+        writer.write_all(&indices[..])?;
+        writer.flush()?;
+        // Left unfinished, to look at what was written so far.
+        drop(writer);
+
+        // The code size byte, then full sub-blocks of a length byte and 255 bytes of data.
+        assert!(output.len() > 1);
+        assert_eq!((output.len() - 1) % 256, 0);
+        for sub_block in output[1..].chunks(256) {
+            assert_eq!(sub_block[0], 255);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn image_data_writer_rejects_big_indices() -> Result<(), EncodingError> {
+        let mut writer = ImageDataWriter::new(vec![], 2)?;
+
+        let error = writer.write_all(&[0, 1, 4]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+
+        // Nothing was consumed, the section goes on.
+        writer.write_all(&[0, 0, 1, 3])?;
+        assert_eq!(writer.finish()?, [0x02, 0x03, 0x04, 0x32, 0x05, 0x00]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn image_data_writer_refuses_writes_after_a_failure() -> Result<(), EncodingError> {
+        let indices = include_bytes!("../../test-assets/sunflower.bmp");
+
+        // Room for the code size byte and a single sub-block.
+        let mut output = [0; 257];
+        let mut writer = ImageDataWriter::new(&mut output[..], 8)?;
+        assert!(writer.write_all(&indices[..]).is_err());
+
+        assert_eq!(writer.write(&[0]).unwrap_err().kind(), io::ErrorKind::Other);
+        assert_eq!(writer.flush().unwrap_err().kind(), io::ErrorKind::Other);
+        assert!(matches!(writer.finish(), Err(EncodingError::Io(_))));
+
+        Ok(())
+    }
+}
@@ -1,37 +1,350 @@
-use std::io::{Read, Write};
+//! Bit packed readers and writers, the building blocks of the LZW encoders and decoders.
+//!
+//! Values of up to 16 bits are packed one after the other, either from the least significant
+//! bit of each byte with the little endian flavor, or from the most significant one with the
+//! big endian flavor.
+//!
+//! Reading or writing 0 bits does nothing, while asking for more than 16 bits fails with
+//! an [ErrorKind::InvalidInput] error.
+//!
+//! The [Read], [Write] and [Error] used throughout the crate are the `std::io` ones. Without
+//! the `std` feature, minimal stand-ins with the same signatures take their place.
+//!
+//! # Examples
+//! ```
+//! use salzweg::io::{BitReader, BitWriter, LittleEndianReader, LittleEndianWriter};
+//!
+//! fn main() -> Result<(), std::io::Error> {
+//!     let mut writer = LittleEndianWriter::new(vec![]);
+//!     writer.write(0x4, 3)?;
+//!     writer.write(0x2, 3)?;
+//!     writer.write(0x1f, 5)?;
+//!     writer.fill()?;
+//!     let packed = writer.into_inner();
+//!
+//!     assert_eq!(packed, [0xd4, 0x07]);
+//!
+//!     let mut reader = LittleEndianReader::new(&packed[..]);
+//!     assert_eq!(reader.read_one(3)?, 0x4);
+//!     assert_eq!(reader.read_one(3)?, 0x2);
+//!     assert_eq!(reader.read_one(5)?, 0x1f);
+//!     Ok(())
+//! }
+//! ```
+use alloc::{format, vec, vec::Vec};
+
+use crate::Endianness;
+
+#[cfg(not(feature = "std"))]
+pub use crate::core_io::{Error, ErrorKind, Read, Write};
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind, Read, Write};
+
+/// The biggest amount of bits that can be read or written at once, the size of a [u16].
+const MAX_AMOUNT: u8 = 16;
+
+#[inline(always)]
+fn check_amount(amount: u8) -> Result<(), Error> {
+    if amount > MAX_AMOUNT {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Can't pack more than {MAX_AMOUNT} bits at once, was {amount}"),
+        ));
+    }
+
+    Ok(())
+}
+
+#[inline(always)]
+fn unexpected_end() -> Error {
+    Error::new(
+        ErrorKind::UnexpectedEof,
+        "The data ended in the middle of a value",
+    )
+}
+
+/// How the data ended, with `bits_left` bits too few for a whole value: less than a byte is
+/// only the padding of the last byte.
+#[inline]
+fn ended_with(bits_left: u8) -> Result<Option<u16>, Error> {
+    if bits_left < 8 {
+        Ok(None)
+    } else {
+        Err(unexpected_end())
+    }
+}
+
+/// Read as many bytes as available to fill `buf`, returning how many were read: 0 once the data
+/// ended.
+#[inline]
+pub(crate) fn read_chunk<R: Read>(read: &mut R, buf: &mut [u8]) -> Result<usize, Error> {
+    loop {
+        match read.read(buf) {
+            Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+            result => return result,
+        }
+    }
+}
+
+/// The most the `to_vec` helpers reserve up front from a [remaining_hint]: the hint could be
+/// off, past this the output only grows with what is actually written.
+pub(crate) const MAX_RESERVED_OUTPUT: usize = 1 << 20;
+
+/// How many bytes are left in `read`, as far as it can tell without reading anything: exact for
+/// slices and the readers wrapping them, 0 when unknown. Only ever a hint, to size a buffer.
+#[cfg(feature = "std")]
+// Nothing is read: the iterator is only asked for its size hint.
+#[allow(clippy::unbuffered_bytes)]
+pub(crate) fn remaining_hint<R: Read>(read: &mut R) -> usize {
+    read.bytes().size_hint().0
+}
+
+/// Without `std`, readers can't tell how much data they hold.
+#[cfg(not(feature = "std"))]
+pub(crate) fn remaining_hint<R: Read>(_read: &mut R) -> usize {
+    0
+}
+
+/// How many bytes the readers fetch from their inner reader at once.
+const READ_AHEAD_SIZE: usize = 64;
+
+/// Bytes fetched ahead from a reader, handed out to the bit buffers of the readers up to 8 at
+/// a time, so that refilling them is a single load rather than one `read` per byte.
+struct ReadAhead<R>
+where
+    R: Read,
+{
+    read: R,
+    // Room for 8 extra bytes, so that 8 bytes can always be loaded from `start`. Only the
+    // first `end - start` of those are valid.
+    buffer: [u8; READ_AHEAD_SIZE + 8],
+    start: usize,
+    end: usize,
+}
+
+impl<R> ReadAhead<R>
+where
+    R: Read,
+{
+    fn new(read: R) -> Self {
+        Self {
+            read,
+            buffer: [0; READ_AHEAD_SIZE + 8],
+            start: 0,
+            end: 0,
+        }
+    }
+
+    /// Swap the inner reader, dropping the bytes fetched ahead.
+    fn reset(&mut self, read: R) {
+        self.read = read;
+        self.clear();
+    }
+
+    /// The bytes fetched ahead, not taken yet.
+    fn remaining(&self) -> &[u8] {
+        &self.buffer[self.start..self.end]
+    }
+
+    /// Drop the bytes fetched ahead, keeping the inner reader.
+    fn clear(&mut self) {
+        self.start = 0;
+        self.end = 0;
+    }
+
+    /// Take up to `max` bytes, `max` being at most 8, returning them along with how many
+    /// are valid: 0 once the data ended.
+    ///
+    /// The inner reader is only read from once all bytes fetched ahead were taken.
+    #[inline(always)]
+    fn take(&mut self, max: usize) -> Result<([u8; 8], usize), Error> {
+        if self.start == self.end {
+            self.start = 0;
+            self.end = read_chunk(&mut self.read, &mut self.buffer[..READ_AHEAD_SIZE])?;
+        }
 
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&self.buffer[self.start..self.start + 8]);
+        let count = max.min(self.end - self.start);
+        self.start += count;
+        Ok((bytes, count))
+    }
+}
+
+/// Reads values packed on a given amount of bits.
 pub trait BitReader: Sized {
-    fn read_one(&mut self, amount: u8) -> Result<u16, std::io::Error>;
-    fn read(&mut self, amount: u8, buf: &mut [u16]) -> Result<usize, std::io::Error>;
-    fn iter(&mut self, amount: u8) -> BitReaderIterator<Self> {
+    /// Read a single value of `amount` bits, `amount` being at most 16.
+    ///
+    /// Fails with [ErrorKind::UnexpectedEof] if the data ends before.
+    fn read_one(&mut self, amount: u8) -> Result<u16, Error>;
+    /// Read values of `amount` bits to fill `buf`, returning how many were read.
+    ///
+    /// Less values than the size of `buf` are read only if the data ends.
+    fn read(&mut self, amount: u8, buf: &mut [u16]) -> Result<usize, Error>;
+    /// Same as [BitReader::read_one], telling apart the two ways the data can end: `None`
+    /// when it ends cleanly, with at most the padding of the last byte left, and an
+    /// [ErrorKind::UnexpectedEof] error when it ends in the middle of a value.
+    ///
+    /// The default implementation can't see the padding: it returns `None` whenever less
+    /// than a whole value is left.
+    fn read_one_or_end(&mut self, amount: u8) -> Result<Option<u16>, Error> {
+        let mut buf = [0];
+        Ok(match self.read(amount, &mut buf)? {
+            0 => None,
+            _ => Some(buf[0]),
+        })
+    }
+    /// Iterate over the values of `amount` bits, until the data ends.
+    ///
+    /// With an `amount` of 0, the iterator never ends.
+    fn iter(&mut self, amount: u8) -> BitReaderIterator<'_, Self> {
         BitReaderIterator::new(self, amount)
     }
 }
 
+impl<B> BitReader for &mut B
+where
+    B: BitReader,
+{
+    #[inline(always)]
+    fn read_one(&mut self, amount: u8) -> Result<u16, Error> {
+        (**self).read_one(amount)
+    }
+
+    #[inline(always)]
+    fn read(&mut self, amount: u8, buf: &mut [u16]) -> Result<usize, Error> {
+        (**self).read(amount, buf)
+    }
+
+    #[inline(always)]
+    fn read_one_or_end(&mut self, amount: u8) -> Result<Option<u16>, Error> {
+        (**self).read_one_or_end(amount)
+    }
+}
+
+/// Reads values packed in little endian order: starting from the least significant bit
+/// of each byte, as done by GIF.
+///
+/// The inner reader is read from 64 bytes at a time, so it needs no buffering of its own:
+/// a file reads as fast through a `BufReader` as a slice does.
 pub struct LittleEndianReader<R>
 where
     R: Read,
 {
-    read: R,
+    source: ReadAhead<R>,
     cursor: u8,
-    byte_buffer: u32,
-    read_buffer: [u8; 1],
+    byte_buffer: u64,
+    /// How many bytes were moved to the bit buffer so far.
+    bytes_loaded: u64,
 }
 
 impl<R> LittleEndianReader<R>
 where
     R: Read,
 {
+    /// Create a reader, consuming the bytes of `read`.
     pub fn new(read: R) -> Self {
         let cursor = 0;
         let byte_buffer = 0;
-        let read_buffer = [0; 1];
         Self {
-            read,
+            source: ReadAhead::new(read),
             cursor,
             byte_buffer,
-            read_buffer,
+            bytes_loaded: 0,
+        }
+    }
+
+    /// How many bytes of the inner reader the values read so far came from.
+    ///
+    /// A byte counts as consumed as soon as one of its bits was read, so that after the last
+    /// value of a stream, padded up to the end of its byte, this is the length of the stream.
+    /// Bytes fetched ahead of the values read don't count.
+    pub fn bytes_consumed(&self) -> u64 {
+        // The bits left form the end of a partly read byte, followed by whole bytes.
+        self.bytes_loaded - (self.cursor / 8) as u64
+    }
+
+    /// Get a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.source.read
+    }
+
+    /// Get a mutable reference to the inner reader.
+    ///
+    /// The reader fetches bytes ahead of what it hands out: reading from the inner reader
+    /// directly skips those.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.source.read
+    }
+
+    /// Unwrap the inner reader. Bytes already read from it but not consumed yet are lost.
+    pub fn into_inner(self) -> R {
+        self.source.read
+    }
+
+    /// Unwrap the inner reader, along with the bytes already read from it but not consumed
+    /// yet: the data goes on with those bytes, then with what the inner reader still holds.
+    ///
+    /// As with [bytes_consumed](Self::bytes_consumed), a partly read byte counts as consumed.
+    pub fn into_parts(self) -> (R, Vec<u8>) {
+        // The bits left form the end of a partly read byte, followed by whole bytes.
+        let whole_bytes = usize::from(self.cursor / 8);
+        let mut remaining = Vec::with_capacity(whole_bytes + self.source.remaining().len());
+        remaining.extend_from_slice(
+            &(self.byte_buffer >> (self.cursor % 8)).to_le_bytes()[..whole_bytes],
+        );
+        remaining.extend_from_slice(self.source.remaining());
+
+        (self.source.read, remaining)
+    }
+
+    /// Start over reading from `read`, as a new reader would, without creating one.
+    ///
+    /// Bits and bytes left from the previous reader are dropped.
+    pub fn reset(&mut self, read: R) {
+        self.source.reset(read);
+        self.cursor = 0;
+        self.byte_buffer = 0;
+        self.bytes_loaded = 0;
+    }
+
+    /// Drop the bits and bytes left, keeping the inner reader: reading goes on from wherever
+    /// the inner reader currently is, as a new reader would.
+    #[cfg(feature = "std")]
+    pub(crate) fn clear(&mut self) {
+        self.source.clear();
+        self.cursor = 0;
+        self.byte_buffer = 0;
+        self.bytes_loaded = 0;
+    }
+
+    /// Top up the bit buffer with as many whole bytes as fit, returning `false` once the
+    /// data ended.
+    #[inline(always)]
+    fn refill(&mut self) -> Result<bool, Error> {
+        let free = ((64 - self.cursor) / 8) as usize;
+        let (bytes, count) = self.source.take(free)?;
+        if count == 0 {
+            return Ok(false);
         }
+
+        // Only the first `count` bytes are valid, the others are masked out.
+        let mask = u64::MAX >> (64 - count * 8);
+        self.byte_buffer |= (u64::from_le_bytes(bytes) & mask) << self.cursor;
+        self.cursor += count as u8 * 8;
+        self.bytes_loaded += count as u64;
+        Ok(true)
+    }
+}
+
+/// A reader over `R::default()`, like an empty slice or `std::io::Empty`, to
+/// [reset](LittleEndianReader::reset) onto the actual data once it is known.
+impl<R> Default for LittleEndianReader<R>
+where
+    R: Read + Default,
+{
+    fn default() -> Self {
+        Self::new(R::default())
     }
 }
 
@@ -40,31 +353,44 @@ where
     R: Read,
 {
     #[inline(always)]
-    fn read_one(&mut self, amount: u8) -> Result<u16, std::io::Error> {
+    fn read_one(&mut self, amount: u8) -> Result<u16, Error> {
+        self.read_one_or_end(amount)?.ok_or_else(unexpected_end)
+    }
+
+    #[inline(always)]
+    fn read_one_or_end(&mut self, amount: u8) -> Result<Option<u16>, Error> {
+        check_amount(amount)?;
+        if amount == 0 {
+            return Ok(Some(0));
+        }
+
         while self.cursor < amount {
-            self.read.read_exact(&mut self.read_buffer[..])?;
-            self.byte_buffer |= (self.read_buffer[0] as u32) << self.cursor;
-            self.cursor += 8;
+            if !self.refill()? {
+                return ended_with(self.cursor);
+            }
         }
 
         let mask = (1 << amount) - 1;
         let data = (self.byte_buffer & mask) as u16;
         self.byte_buffer >>= amount;
         self.cursor -= amount;
-        Ok(data)
+        Ok(Some(data))
     }
 
     #[inline(always)]
-    fn read(&mut self, amount: u8, buf: &mut [u16]) -> Result<usize, std::io::Error> {
+    fn read(&mut self, amount: u8, buf: &mut [u16]) -> Result<usize, Error> {
+        check_amount(amount)?;
+        if amount == 0 {
+            buf.fill(0);
+            return Ok(buf.len());
+        }
+
         let mut done = 0;
         while done < buf.len() {
             while self.cursor < amount {
-                if self.read.read(&mut self.read_buffer[..])? == 0 {
+                if !self.refill()? {
                     return Ok(done);
                 }
-
-                self.byte_buffer |= (self.read_buffer[0] as u32) << self.cursor;
-                self.cursor += 8;
             }
 
             let mask = (1 << amount) - 1;
@@ -78,31 +404,134 @@ where
     }
 }
 
+/// Reads values packed in big endian order: starting from the most significant bit
+/// of each byte, as done by TIFF.
+///
+/// Like the [LittleEndianReader], it reads from the inner reader 64 bytes at a time.
 pub struct BigEndianReader<R>
 where
     R: Read,
 {
-    read: R,
+    source: ReadAhead<R>,
     cursor: u8,
-    byte_buffer: u32,
-    read_buffer: [u8; 1],
+    byte_buffer: u64,
+    /// How many bytes were moved to the bit buffer so far.
+    bytes_loaded: u64,
 }
 
 impl<R> BigEndianReader<R>
 where
     R: Read,
 {
+    /// Create a reader, consuming the bytes of `read`.
     pub fn new(read: R) -> Self {
         let cursor = 0;
         let byte_buffer = 0;
-        let read_buffer = [0; 1];
         Self {
-            read,
+            source: ReadAhead::new(read),
             cursor,
             byte_buffer,
-            read_buffer,
+            bytes_loaded: 0,
         }
     }
+
+    /// How many bytes of the inner reader the values read so far came from.
+    ///
+    /// A byte counts as consumed as soon as one of its bits was read, so that after the last
+    /// value of a stream, padded up to the end of its byte, this is the length of the stream.
+    /// Bytes fetched ahead of the values read don't count.
+    pub fn bytes_consumed(&self) -> u64 {
+        // The bits left form the end of a partly read byte, followed by whole bytes.
+        self.bytes_loaded - (self.cursor / 8) as u64
+    }
+
+    /// Get a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.source.read
+    }
+
+    /// Get a mutable reference to the inner reader.
+    ///
+    /// The reader fetches bytes ahead of what it hands out: reading from the inner reader
+    /// directly skips those.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.source.read
+    }
+
+    /// Unwrap the inner reader. Bytes already read from it but not consumed yet are lost.
+    pub fn into_inner(self) -> R {
+        self.source.read
+    }
+
+    /// Unwrap the inner reader, along with the bytes already read from it but not consumed
+    /// yet: the data goes on with those bytes, then with what the inner reader still holds.
+    ///
+    /// As with [bytes_consumed](Self::bytes_consumed), a partly read byte counts as consumed.
+    pub fn into_parts(self) -> (R, Vec<u8>) {
+        // The bits left form the end of a partly read byte, followed by whole bytes.
+        let whole_bytes = usize::from(self.cursor / 8);
+        let mut remaining = Vec::with_capacity(whole_bytes + self.source.remaining().len());
+        remaining.extend_from_slice(
+            &(self.byte_buffer << (self.cursor % 8)).to_be_bytes()[..whole_bytes],
+        );
+        remaining.extend_from_slice(self.source.remaining());
+
+        (self.source.read, remaining)
+    }
+
+    /// Start over reading from `read`, as a new reader would, without creating one.
+    ///
+    /// Bits and bytes left from the previous reader are dropped.
+    pub fn reset(&mut self, read: R) {
+        self.source.reset(read);
+        self.cursor = 0;
+        self.byte_buffer = 0;
+        self.bytes_loaded = 0;
+    }
+
+    /// Top up the bit buffer with as many whole bytes as fit, returning `false` once the
+    /// data ended.
+    #[inline(always)]
+    fn refill(&mut self) -> Result<bool, Error> {
+        let free = ((64 - self.cursor) / 8) as usize;
+        let (bytes, count) = self.source.take(free)?;
+        if count == 0 {
+            return Ok(false);
+        }
+
+        // Only the first `count` bytes are valid, the others are masked out.
+        let mask = !(u64::MAX >> (count * 8 - 1) >> 1);
+        self.byte_buffer |= (u64::from_be_bytes(bytes) & mask) >> self.cursor;
+        self.cursor += count as u8 * 8;
+        self.bytes_loaded += count as u64;
+        Ok(true)
+    }
+
+    /// Drop the bits left in the current byte, so that the next read starts on a new byte.
+    pub(crate) fn skip_to_byte_boundary(&mut self) {
+        let skipped = self.cursor % 8;
+        self.byte_buffer <<= skipped;
+        self.cursor -= skipped;
+    }
+
+    /// Whether there are bits left to read.
+    pub(crate) fn has_remaining_data(&mut self) -> Result<bool, Error> {
+        if self.cursor > 0 {
+            return Ok(true);
+        }
+        self.refill()
+    }
+}
+
+/// A reader over `R::default()`, like an empty slice or `std::io::Empty`, to
+/// [reset](BigEndianReader::reset) onto the actual data once it is known.
+impl<R> Default for BigEndianReader<R>
+where
+    R: Read + Default,
+{
+    fn default() -> Self {
+        Self::new(R::default())
+    }
 }
 
 impl<R> BitReader for BigEndianReader<R>
@@ -110,38 +539,50 @@ where
     R: Read,
 {
     #[inline(always)]
-    fn read_one(&mut self, amount: u8) -> Result<u16, std::io::Error> {
+    fn read_one(&mut self, amount: u8) -> Result<u16, Error> {
+        self.read_one_or_end(amount)?.ok_or_else(unexpected_end)
+    }
+
+    #[inline(always)]
+    fn read_one_or_end(&mut self, amount: u8) -> Result<Option<u16>, Error> {
+        check_amount(amount)?;
+        if amount == 0 {
+            return Ok(Some(0));
+        }
+
         while self.cursor < amount {
-            self.read.read_exact(&mut self.read_buffer[..])?;
-            let shift = 24 - self.cursor;
-            self.byte_buffer |= (self.read_buffer[0] as u32) << shift;
-            self.cursor += 8;
+            if !self.refill()? {
+                return ended_with(self.cursor);
+            }
         }
 
         let mask = (1 << amount) - 1;
-        let shift = 32 - amount;
+        let shift = 64 - amount;
         let data = ((self.byte_buffer >> shift) & mask) as u16;
         self.byte_buffer <<= amount;
         self.cursor -= amount;
 
-        Ok(data)
+        Ok(Some(data))
     }
 
     #[inline(always)]
-    fn read(&mut self, amount: u8, buf: &mut [u16]) -> Result<usize, std::io::Error> {
+    fn read(&mut self, amount: u8, buf: &mut [u16]) -> Result<usize, Error> {
+        check_amount(amount)?;
+        if amount == 0 {
+            buf.fill(0);
+            return Ok(buf.len());
+        }
+
         let mut done = 0;
         while done < buf.len() {
             while self.cursor < amount {
-                if self.read.read(&mut self.read_buffer[..])? == 0 {
+                if !self.refill()? {
                     return Ok(done);
                 }
-                let shift = 24 - self.cursor;
-                self.byte_buffer |= (self.read_buffer[0] as u32) << shift;
-                self.cursor += 8;
             }
 
             let mask = (1 << amount) - 1;
-            let shift = 32 - amount;
+            let shift = 64 - amount;
             buf[done] = ((self.byte_buffer >> shift) & mask) as u16;
             self.byte_buffer <<= amount;
             self.cursor -= amount;
@@ -152,6 +593,7 @@ where
     }
 }
 
+/// Iterator over the values of a [BitReader], created by [BitReader::iter].
 pub struct BitReaderIterator<'a, B>
 where
     B: BitReader,
@@ -178,7 +620,7 @@ impl<'a, B> Iterator for BitReaderIterator<'a, B>
 where
     B: BitReader,
 {
-    type Item = Result<u16, std::io::Error>;
+    type Item = Result<u16, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
         match self.reader.read(self.amount, &mut self.buf[..]) {
@@ -194,19 +636,130 @@ where
     }
 }
 
+/// Writes values packed on a given amount of bits.
 pub trait BitWriter {
-    fn write(&mut self, data: u16, amount: u8) -> Result<(), std::io::Error>;
+    /// Write the `amount` lowest bits of `data`, `amount` being at most 16.
+    ///
+    /// Full bytes are written right away, unless the writer holds them back to write them in
+    /// bulk: they are then all written by the next [BitWriter::fill] or [BitWriter::flush].
+    /// The remaining bits wait for the next values.
+    fn write(&mut self, data: u16, amount: u8) -> Result<(), Error>;
+
+    /// Write each of `codes` on `amount` bits, `amount` being at most 16: the same as calling
+    /// [BitWriter::write] for each of them, in a single call.
+    fn write_many(&mut self, codes: &[u16], amount: u8) -> Result<(), Error> {
+        for &code in codes {
+            self.write(code, amount)?;
+        }
+
+        Ok(())
+    }
 
-    fn fill(&mut self) -> Result<(), std::io::Error>;
+    /// Write the remaining bits, if any, padding the last byte with zeros, along with the
+    /// bytes held back.
+    ///
+    /// The values written afterwards start a new byte: segments filled one after the other
+    /// are concatenated, each of them starting on a byte boundary.
+    fn fill(&mut self) -> Result<(), Error>;
 
-    fn flush(&mut self) -> Result<(), std::io::Error>;
+    /// Write the bytes held back, then flush the inner writer. Remaining bits are not
+    /// written, see [BitWriter::fill].
+    fn flush(&mut self) -> Result<(), Error>;
 }
 
-pub struct LittleEndianWriter<W>
+impl<B> BitWriter for &mut B
+where
+    B: BitWriter,
+{
+    #[inline(always)]
+    fn write(&mut self, data: u16, amount: u8) -> Result<(), Error> {
+        (**self).write(data, amount)
+    }
+
+    #[inline(always)]
+    fn write_many(&mut self, codes: &[u16], amount: u8) -> Result<(), Error> {
+        (**self).write_many(codes, amount)
+    }
+
+    fn fill(&mut self) -> Result<(), Error> {
+        (**self).fill()
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        (**self).flush()
+    }
+}
+
+/// How many bytes the buffered writers hold back before writing them to their inner writer
+/// at once.
+const WRITE_BEHIND_SIZE: usize = 64;
+
+/// Bytes packed by the writers, held back until `capacity` of them can be written to the
+/// inner writer in bulk. With a capacity of 1, every byte is written right away.
+#[derive(Clone)]
+struct WriteBehind<W>
 where
     W: Write,
 {
     write: W,
+    buffer: [u8; WRITE_BEHIND_SIZE],
+    len: usize,
+    capacity: usize,
+}
+
+impl<W> WriteBehind<W>
+where
+    W: Write,
+{
+    fn new(write: W, capacity: usize) -> Self {
+        Self {
+            write,
+            buffer: [0; WRITE_BEHIND_SIZE],
+            len: 0,
+            capacity,
+        }
+    }
+
+    /// Swap the inner writer, dropping the bytes held back.
+    fn reset(&mut self, write: W) {
+        self.write = write;
+        self.len = 0;
+    }
+
+    #[inline(always)]
+    fn push(&mut self, byte: u8) -> Result<(), Error> {
+        // Still full if writing failed: try again before taking more.
+        if self.len == self.capacity {
+            self.drain()?;
+        }
+        self.buffer[self.len] = byte;
+        self.len += 1;
+        if self.len == self.capacity {
+            self.drain()?;
+        }
+
+        Ok(())
+    }
+
+    /// Write all the bytes held back to the inner writer.
+    fn drain(&mut self) -> Result<(), Error> {
+        if self.len > 0 {
+            self.write.write_all(&self.buffer[..self.len])?;
+            self.len = 0;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes values packed in little endian order: starting from the least significant bit
+/// of each byte, as done by GIF.
+#[derive(Clone)]
+pub struct LittleEndianWriter<W>
+where
+    W: Write,
+{
+    sink: WriteBehind<W>,
     cursor: u8,
     byte_buffer: u32,
 }
@@ -215,23 +768,92 @@ impl<W> LittleEndianWriter<W>
 where
     W: Write,
 {
+    /// Create a writer, writing the packed bytes to `write` as soon as they are complete.
     pub fn new(write: W) -> Self {
+        Self::with_capacity(write, 1)
+    }
+
+    /// Create a writer holding up to 64 packed bytes back, to write them to `write` in bulk
+    /// rather than one at a time. Worth it when `write` is not buffered, like a file.
+    ///
+    /// The bytes held back only reach `write` on [BitWriter::fill] or [BitWriter::flush].
+    pub fn buffered(write: W) -> Self {
+        Self::with_capacity(write, WRITE_BEHIND_SIZE)
+    }
+
+    fn with_capacity(write: W, capacity: usize) -> Self {
         let byte_buffer = 0;
         let cursor = 0;
         Self {
-            write,
+            sink: WriteBehind::new(write, capacity),
             byte_buffer,
             cursor,
         }
     }
-}
 
-impl<W> BitWriter for LittleEndianWriter<W>
-where
-    W: Write,
-{
-    #[inline]
-    fn write(&mut self, data: u16, amount: u8) -> Result<(), std::io::Error> {
+    /// Get a reference to the inner writer.
+    ///
+    /// It holds all the complete bytes written so far, except for a
+    /// [buffered](Self::buffered) writer: bytes held back only reach it on [BitWriter::fill]
+    /// or [BitWriter::flush].
+    pub fn get_ref(&self) -> &W {
+        &self.sink.write
+    }
+
+    /// Get a mutable reference to the inner writer.
+    ///
+    /// Bytes held back by a [buffered](Self::buffered) writer only reach it on
+    /// [BitWriter::fill] or [BitWriter::flush].
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.sink.write
+    }
+
+    /// Unwrap the inner writer. Remaining bits and bytes held back are lost, unless
+    /// [BitWriter::fill] was called.
+    pub fn into_inner(self) -> W {
+        self.sink.write
+    }
+
+    /// Start over writing to `write`, as a new writer would, without creating one.
+    ///
+    /// Remaining bits and bytes held back are dropped, unless [BitWriter::fill] was called.
+    pub fn reset(&mut self, write: W) {
+        self.sink.reset(write);
+        self.cursor = 0;
+        self.byte_buffer = 0;
+    }
+
+    /// Write the remaining bits, padding the last byte with zeros, flush, then unwrap the
+    /// inner writer.
+    pub fn finish(mut self) -> Result<W, Error> {
+        self.fill()?;
+        self.flush()?;
+
+        Ok(self.sink.write)
+    }
+
+    /// Same as [BitWriter::fill], padding the last byte with ones when `bit` is `true`.
+    pub fn fill_with(&mut self, bit: bool) -> Result<(), Error> {
+        if self.cursor > 0 {
+            // The remaining bits are the lowest ones, the padding goes above.
+            let padding = if bit { u8::MAX << self.cursor } else { 0 };
+            self.sink.push(self.byte_buffer as u8 | padding)?;
+            self.byte_buffer = 0;
+            self.cursor = 0;
+        }
+
+        self.sink.drain()
+    }
+
+    /// Whether the values written so far end on a byte boundary, as they do right after a
+    /// [BitWriter::fill]: the next value then starts a new byte.
+    pub fn is_byte_aligned(&self) -> bool {
+        self.cursor == 0
+    }
+
+    /// Write the `amount` lowest bits of `data`, `amount` being already checked and not 0.
+    #[inline(always)]
+    fn push_bits(&mut self, data: u16, amount: u8) -> Result<(), Error> {
         let mask = (1 << amount) - 1;
         self.byte_buffer |= (data as u32 & mask) << self.cursor;
         self.cursor += amount;
@@ -241,95 +863,1085 @@ where
             self.byte_buffer >>= 8;
             self.cursor -= 8;
 
-            self.write.write_all(&[byte])?;
+            self.sink.push(byte)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A writer to `W::default()`, like an empty [Vec] or `std::io::Sink`, to
+/// [reset](LittleEndianWriter::reset) onto the actual output once it is known.
+impl<W> Default for LittleEndianWriter<W>
+where
+    W: Write + Default,
+{
+    fn default() -> Self {
+        Self::new(W::default())
+    }
+}
+
+impl<W> BitWriter for LittleEndianWriter<W>
+where
+    W: Write,
+{
+    #[inline]
+    fn write(&mut self, data: u16, amount: u8) -> Result<(), Error> {
+        check_amount(amount)?;
+        if amount == 0 {
+            return Ok(());
+        }
+
+        self.push_bits(data, amount)
+    }
+
+    #[inline]
+    fn write_many(&mut self, codes: &[u16], amount: u8) -> Result<(), Error> {
+        check_amount(amount)?;
+        if amount == 0 {
+            return Ok(());
+        }
+
+        for &code in codes {
+            self.push_bits(code, amount)?;
         }
 
-        Ok(())
+        Ok(())
+    }
+
+    #[inline]
+    fn fill(&mut self) -> Result<(), Error> {
+        self.fill_with(false)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Error> {
+        self.sink.drain()?;
+        self.sink.write.flush()
+    }
+}
+
+/// Writes values packed in big endian order: starting from the most significant bit
+/// of each byte, as done by TIFF.
+#[derive(Clone)]
+pub struct BigEndianWriter<W>
+where
+    W: Write,
+{
+    sink: WriteBehind<W>,
+    cursor: u8,
+    byte_buffer: u32,
+}
+
+impl<W> BigEndianWriter<W>
+where
+    W: Write,
+{
+    /// Create a writer, writing the packed bytes to `write` as soon as they are complete.
+    pub fn new(write: W) -> Self {
+        Self::with_capacity(write, 1)
+    }
+
+    /// Create a writer holding up to 64 packed bytes back, to write them to `write` in bulk
+    /// rather than one at a time. Worth it when `write` is not buffered, like a file.
+    ///
+    /// The bytes held back only reach `write` on [BitWriter::fill] or [BitWriter::flush].
+    pub fn buffered(write: W) -> Self {
+        Self::with_capacity(write, WRITE_BEHIND_SIZE)
+    }
+
+    fn with_capacity(write: W, capacity: usize) -> Self {
+        let byte_buffer = 0;
+        let cursor = 0;
+        Self {
+            sink: WriteBehind::new(write, capacity),
+            byte_buffer,
+            cursor,
+        }
+    }
+
+    /// Get a reference to the inner writer.
+    ///
+    /// It holds all the complete bytes written so far, except for a
+    /// [buffered](Self::buffered) writer: bytes held back only reach it on [BitWriter::fill]
+    /// or [BitWriter::flush].
+    pub fn get_ref(&self) -> &W {
+        &self.sink.write
+    }
+
+    /// Get a mutable reference to the inner writer.
+    ///
+    /// Bytes held back by a [buffered](Self::buffered) writer only reach it on
+    /// [BitWriter::fill] or [BitWriter::flush].
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.sink.write
+    }
+
+    /// Unwrap the inner writer. Remaining bits and bytes held back are lost, unless
+    /// [BitWriter::fill] was called.
+    pub fn into_inner(self) -> W {
+        self.sink.write
+    }
+
+    /// Start over writing to `write`, as a new writer would, without creating one.
+    ///
+    /// Remaining bits and bytes held back are dropped, unless [BitWriter::fill] was called.
+    pub fn reset(&mut self, write: W) {
+        self.sink.reset(write);
+        self.cursor = 0;
+        self.byte_buffer = 0;
+    }
+
+    /// Write the remaining bits, padding the last byte with zeros, flush, then unwrap the
+    /// inner writer.
+    pub fn finish(mut self) -> Result<W, Error> {
+        self.fill()?;
+        self.flush()?;
+
+        Ok(self.sink.write)
+    }
+
+    /// Same as [BitWriter::fill], padding the last byte with ones when `bit` is `true`.
+    pub fn fill_with(&mut self, bit: bool) -> Result<(), Error> {
+        if self.cursor > 0 {
+            // The remaining bits are the highest ones, the padding goes below.
+            let padding = if bit { u8::MAX >> self.cursor } else { 0 };
+            self.sink.push((self.byte_buffer >> 24) as u8 | padding)?;
+            self.byte_buffer = 0;
+            self.cursor = 0;
+        }
+
+        self.sink.drain()
+    }
+
+    /// Whether the values written so far end on a byte boundary, as they do right after a
+    /// [BitWriter::fill]: the next value then starts a new byte.
+    pub fn is_byte_aligned(&self) -> bool {
+        self.cursor == 0
+    }
+
+    /// Write the `amount` lowest bits of `data`, `amount` being already checked and not 0.
+    #[inline(always)]
+    fn push_bits(&mut self, data: u16, amount: u8) -> Result<(), Error> {
+        // Less than a byte is ever pending, so with at most 16 bits the shift can't underflow.
+        debug_assert!(self.cursor < 8);
+        let mask = (1 << amount) - 1;
+        let shift = 32 - amount - self.cursor;
+        self.byte_buffer |= (data as u32 & mask) << shift;
+        self.cursor += amount;
+
+        while self.cursor >= 8 {
+            let byte = (self.byte_buffer >> 24) as u8;
+            self.byte_buffer <<= 8;
+            self.cursor -= 8;
+
+            self.sink.push(byte)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A writer to `W::default()`, like an empty [Vec] or `std::io::Sink`, to
+/// [reset](BigEndianWriter::reset) onto the actual output once it is known.
+impl<W> Default for BigEndianWriter<W>
+where
+    W: Write + Default,
+{
+    fn default() -> Self {
+        Self::new(W::default())
+    }
+}
+
+impl<W> BitWriter for BigEndianWriter<W>
+where
+    W: Write,
+{
+    #[inline]
+    fn write(&mut self, data: u16, amount: u8) -> Result<(), Error> {
+        check_amount(amount)?;
+        if amount == 0 {
+            return Ok(());
+        }
+
+        self.push_bits(data, amount)
+    }
+
+    #[inline]
+    fn write_many(&mut self, codes: &[u16], amount: u8) -> Result<(), Error> {
+        check_amount(amount)?;
+        if amount == 0 {
+            return Ok(());
+        }
+
+        for &code in codes {
+            self.push_bits(code, amount)?;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn fill(&mut self) -> Result<(), Error> {
+        self.fill_with(false)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Error> {
+        self.sink.drain()?;
+        self.sink.write.flush()
+    }
+}
+
+/// Pack each of `codes` on the matching amount of bits of `widths`, with the given bit
+/// ordering: the bit packing of the LZW encoders, without the dictionary.
+///
+/// The last byte is padded with zeros, as [BitWriter::fill] does.
+///
+/// # Errors
+///
+/// Fails with [ErrorKind::InvalidInput] if there isn't exactly one width per code, or for
+/// widths above 16 bits, and on the errors of `into`.
+///
+/// # Examples
+/// ```
+/// use salzweg::{io, Endianness};
+///
+/// fn main() -> Result<(), std::io::Error> {
+///     let mut packed = vec![];
+///     io::pack_codes(
+///         &[4, 0, 0, 1, 3, 5],
+///         &[3, 3, 3, 3, 4, 4],
+///         &mut packed,
+///         Endianness::LittleEndian,
+///     )?;
+///
+///     assert_eq!(packed, [0x04, 0x32, 0x05]);
+///     Ok(())
+/// }
+/// ```
+pub fn pack_codes<W: Write>(
+    codes: &[u16],
+    widths: &[u8],
+    into: W,
+    endianness: Endianness,
+) -> Result<(), Error> {
+    check_widths(codes.len(), widths)?;
+
+    match endianness {
+        Endianness::BigEndian => write_codes(codes, widths, BigEndianWriter::buffered(into)),
+        Endianness::LittleEndian => write_codes(codes, widths, LittleEndianWriter::buffered(into)),
+    }
+}
+
+/// Read one code for each of `widths`, packed with the given bit ordering, as written by
+/// [pack_codes]. Bits left after the last code, like the padding of the last byte, are ignored.
+///
+/// # Errors
+///
+/// Fails with [ErrorKind::UnexpectedEof] if `data` ends before the last code, with
+/// [ErrorKind::InvalidInput] for widths above 16 bits, and on the errors of `data`.
+///
+/// # Examples
+/// ```
+/// use salzweg::{io, Endianness};
+///
+/// fn main() -> Result<(), std::io::Error> {
+///     let data = [0x04, 0x32, 0x05];
+///     let codes = io::unpack_codes(&data[..], &[3, 3, 3, 3, 4, 4], Endianness::LittleEndian)?;
+///
+///     assert_eq!(codes, [4, 0, 0, 1, 3, 5]);
+///     Ok(())
+/// }
+/// ```
+pub fn unpack_codes<R: Read>(
+    data: R,
+    widths: &[u8],
+    endianness: Endianness,
+) -> Result<Vec<u16>, Error> {
+    match endianness {
+        Endianness::BigEndian => read_codes(widths, BigEndianReader::new(data)),
+        Endianness::LittleEndian => read_codes(widths, LittleEndianReader::new(data)),
+    }
+}
+
+fn check_widths(code_count: usize, widths: &[u8]) -> Result<(), Error> {
+    if code_count != widths.len() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Expected one width per code, got {} widths for {code_count} codes",
+                widths.len()
+            ),
+        ));
+    }
+    // Checked before writing anything, rather than failing halfway.
+    widths.iter().try_for_each(|&width| check_amount(width))
+}
+
+fn write_codes<B: BitWriter>(codes: &[u16], widths: &[u8], bit_writer: B) -> Result<(), Error> {
+    let mut bit_writer = bit_writer;
+    for (&code, &width) in codes.iter().zip(widths) {
+        bit_writer.write(code, width)?;
+    }
+    bit_writer.fill()?;
+    bit_writer.flush()
+}
+
+fn read_codes<B: BitReader>(widths: &[u8], bit_reader: B) -> Result<Vec<u16>, Error> {
+    let mut bit_reader = bit_reader;
+    widths
+        .iter()
+        .map(|&width| bit_reader.read_one(width))
+        .collect()
+}
+
+/// Reads data framed into GIF sub-blocks.
+///
+/// Each sub-block starts with a byte giving its length, followed by that many bytes of data.
+/// Reaching the empty block terminating the sequence is reported as the end of the stream,
+/// while running out of data before it is an [ErrorKind::UnexpectedEof] error.
+pub(crate) struct SubBlockReader<R>
+where
+    R: Read,
+{
+    read: R,
+    remaining: usize,
+    terminated: bool,
+}
+
+impl<R> SubBlockReader<R>
+where
+    R: Read,
+{
+    pub fn new(read: R) -> Self {
+        let remaining = 0;
+        let terminated = false;
+        Self {
+            read,
+            remaining,
+            terminated,
+        }
+    }
+
+    /// Whether the block terminator was reached. Reads the next sub-block length if needed.
+    pub fn at_end(&mut self) -> Result<bool, Error> {
+        if self.remaining == 0 && !self.terminated {
+            self.next_block()?;
+        }
+
+        Ok(self.terminated)
+    }
+
+    fn next_block(&mut self) -> Result<(), Error> {
+        let mut length = [0];
+        self.read.read_exact(&mut length).map_err(|error| {
+            if error.kind() == ErrorKind::UnexpectedEof {
+                Error::new(error.kind(), "Missing sub-block terminator")
+            } else {
+                error
+            }
+        })?;
+
+        if length[0] == 0 {
+            self.terminated = true;
+        } else {
+            self.remaining = length[0] as usize;
+        }
+
+        Ok(())
+    }
+}
+
+impl<R> Read for SubBlockReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if buf.is_empty() || self.at_end()? {
+            return Ok(0);
+        }
+
+        let amount = buf.len().min(self.remaining);
+        let read = self.read.read(&mut buf[..amount])?;
+        if read == 0 {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "Sub-block shorter than its length",
+            ));
+        }
+        self.remaining -= read;
+
+        Ok(read)
+    }
+}
+
+/// Frames the written data into GIF sub-blocks.
+///
+/// Each sub-block starts with a byte giving its length, between 1 and 255, followed by
+/// that many bytes of data. The sequence of sub-blocks ends with an empty block, written
+/// by [SubBlockWriter::finish].
+pub(crate) struct SubBlockWriter<W>
+where
+    W: Write,
+{
+    write: W,
+    buffer: [u8; 255],
+    buffer_length: usize,
+}
+
+impl<W> SubBlockWriter<W>
+where
+    W: Write,
+{
+    pub fn new(write: W) -> Self {
+        let buffer = [0; 255];
+        let buffer_length = 0;
+        Self {
+            write,
+            buffer,
+            buffer_length,
+        }
+    }
+
+    /// Write the last, possibly partial, sub-block and the block terminator.
+    pub fn finish(mut self) -> Result<W, Error> {
+        self.write_block()?;
+        self.write.write_all(&[0])?;
+        self.write.flush()?;
+
+        Ok(self.write)
+    }
+
+    fn write_block(&mut self) -> Result<(), Error> {
+        if self.buffer_length > 0 {
+            self.write.write_all(&[self.buffer_length as u8])?;
+            self.write.write_all(&self.buffer[..self.buffer_length])?;
+            self.buffer_length = 0;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W> Write for SubBlockWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        let amount = buf.len().min(self.buffer.len() - self.buffer_length);
+        self.buffer[self.buffer_length..self.buffer_length + amount]
+            .copy_from_slice(&buf[..amount]);
+        self.buffer_length += amount;
+
+        if self.buffer_length == self.buffer.len() {
+            self.write_block()?;
+        }
+
+        Ok(amount)
+    }
+
+    /// Flushes the inner writer, but keeps the current sub-block open: writing a shorter
+    /// sub-block would be valid, but wasteful.
+    fn flush(&mut self) -> Result<(), Error> {
+        self.write.flush()
+    }
+}
+
+/// Counts the bytes written to the inner writer, to know the size of the compressed data
+/// without keeping it around.
+///
+/// # Examples
+/// ```
+/// use salzweg::{encoder::GifStyleEncoder, io::CountingWriter};
+///
+/// let data = [0, 0, 1, 3];
+/// let mut output = CountingWriter::new(std::io::sink());
+///
+/// GifStyleEncoder::encode(&data[..], &mut output, 2).expect("Compression failed");
+///
+/// assert_eq!(output.bytes_written(), 3);
+/// ```
+pub struct CountingWriter<W>
+where
+    W: Write,
+{
+    write: W,
+    bytes_written: u64,
+}
+
+impl<W> CountingWriter<W>
+where
+    W: Write,
+{
+    /// Create a writer, forwarding everything to `write`.
+    pub fn new(write: W) -> Self {
+        let bytes_written = 0;
+        Self {
+            write,
+            bytes_written,
+        }
+    }
+
+    /// How many bytes the inner writer accepted so far.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// [CountingWriter::bytes_written] as a [usize], failing if it doesn't fit, as it can on
+    /// 16 and 32 bit targets.
+    pub(crate) fn bytes_written_usize(&self) -> Result<usize, Error> {
+        usize::try_from(self.bytes_written)
+            .map_err(|_| Error::other("More bytes written than a usize can count"))
+    }
+
+    /// Get a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.write
+    }
+
+    /// Get a mutable reference to the inner writer. Bytes written to it directly are not
+    /// counted.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.write
+    }
+
+    /// Unwrap the inner writer.
+    pub fn into_inner(self) -> W {
+        self.write
+    }
+}
+
+impl<W> Write for CountingWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        let written = self.write.write(buf)?;
+        self.bytes_written += written as u64;
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.write.flush()
+    }
+}
+
+/// Hands out values that are already unpacked, one per code.
+///
+/// A value too wide for the amount of bits asked for fails with [ErrorKind::InvalidData], as
+/// it couldn't have been read from packed data.
+pub(crate) struct CodeReader<'a> {
+    codes: &'a [u16],
+}
+
+impl<'a> CodeReader<'a> {
+    pub fn new(codes: &'a [u16]) -> Self {
+        Self { codes }
+    }
+}
+
+impl BitReader for CodeReader<'_> {
+    fn read_one(&mut self, amount: u8) -> Result<u16, Error> {
+        check_amount(amount)?;
+        let (&code, rest) = self.codes.split_first().ok_or_else(unexpected_end)?;
+        if amount < MAX_AMOUNT && code >> amount != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Code {code} doesn't fit on {amount} bits"),
+            ));
+        }
+
+        self.codes = rest;
+        Ok(code)
+    }
+
+    fn read(&mut self, amount: u8, buf: &mut [u16]) -> Result<usize, Error> {
+        let mut done = 0;
+        while done < buf.len() && !self.codes.is_empty() {
+            buf[done] = self.read_one(amount)?;
+            done += 1;
+        }
+
+        Ok(done)
+    }
+}
+
+/// Collects the values written as they are, without packing them.
+pub(crate) struct CodeWriter<'a> {
+    codes: &'a mut Vec<u16>,
+}
+
+impl<'a> CodeWriter<'a> {
+    pub fn new(codes: &'a mut Vec<u16>) -> Self {
+        Self { codes }
+    }
+}
+
+impl BitWriter for CodeWriter<'_> {
+    fn write(&mut self, data: u16, amount: u8) -> Result<(), Error> {
+        check_amount(amount)?;
+        self.codes.push(data);
+        Ok(())
+    }
+
+    fn fill(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// The TIFF horizontal differencing predictor, for 8 bit samples.
+///
+/// Inside each row of `row_stride` bytes, each sample is stored as the difference with the
+/// same component of the previous pixel. The first pixel of each row is kept as is.
+struct HorizontalPredictor {
+    row_stride: usize,
+    samples_per_pixel: usize,
+    column: usize,
+    // The last sample of each component, as it was before differencing.
+    previous: Vec<u8>,
+}
+
+impl HorizontalPredictor {
+    fn new(row_stride: usize, samples_per_pixel: usize) -> Result<Self, Error> {
+        if row_stride == 0 || samples_per_pixel == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Row stride and samples per pixel must be positive, were {row_stride} and {samples_per_pixel}"
+                ),
+            ));
+        }
+
+        Ok(Self {
+            row_stride,
+            samples_per_pixel,
+            column: 0,
+            previous: vec![0; samples_per_pixel],
+        })
+    }
+
+    /// The sample the next one is predicted from, the same component of the previous pixel.
+    #[inline(always)]
+    fn prediction(&self) -> u8 {
+        if self.column < self.samples_per_pixel {
+            0
+        } else {
+            self.previous[self.column % self.samples_per_pixel]
+        }
+    }
+
+    #[inline(always)]
+    fn advance(&mut self, sample: u8) {
+        self.previous[self.column % self.samples_per_pixel] = sample;
+        self.column += 1;
+        if self.column == self.row_stride {
+            self.column = 0;
+        }
+    }
+
+    fn difference(&mut self, sample: u8) -> u8 {
+        let delta = sample.wrapping_sub(self.prediction());
+        self.advance(sample);
+
+        delta
+    }
+
+    fn undo_difference(&mut self, delta: u8) -> u8 {
+        let sample = delta.wrapping_add(self.prediction());
+        self.advance(sample);
+
+        sample
+    }
+}
+
+/// Applies the TIFF horizontal differencing predictor to the data read.
+pub(crate) struct DifferencingReader<R>
+where
+    R: Read,
+{
+    read: R,
+    predictor: HorizontalPredictor,
+}
+
+impl<R> DifferencingReader<R>
+where
+    R: Read,
+{
+    pub fn new(read: R, row_stride: usize, samples_per_pixel: usize) -> Result<Self, Error> {
+        let predictor = HorizontalPredictor::new(row_stride, samples_per_pixel)?;
+        Ok(Self { read, predictor })
+    }
+}
+
+impl<R> Read for DifferencingReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let read = self.read.read(buf)?;
+        for sample in &mut buf[..read] {
+            *sample = self.predictor.difference(*sample);
+        }
+
+        Ok(read)
+    }
+}
+
+/// Undoes the TIFF horizontal differencing predictor on the data written.
+pub(crate) struct UndifferencingWriter<W>
+where
+    W: Write,
+{
+    write: W,
+    predictor: HorizontalPredictor,
+    buffer: [u8; 256],
+}
+
+impl<W> UndifferencingWriter<W>
+where
+    W: Write,
+{
+    pub fn new(write: W, row_stride: usize, samples_per_pixel: usize) -> Result<Self, Error> {
+        let predictor = HorizontalPredictor::new(row_stride, samples_per_pixel)?;
+        Ok(Self {
+            write,
+            predictor,
+            buffer: [0; 256],
+        })
+    }
+}
+
+impl<W> Write for UndifferencingWriter<W>
+where
+    W: Write,
+{
+    /// Always writes the whole of `buf`: the predictor moved past it, so stopping halfway
+    /// would lose its place.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        for chunk in buf.chunks(self.buffer.len()) {
+            for (sample, &delta) in self.buffer.iter_mut().zip(chunk) {
+                *sample = self.predictor.undo_difference(delta);
+            }
+            self.write.write_all(&self.buffer[..chunk.len()])?;
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.write.flush()
+    }
+}
+
+#[cfg(test)]
+// Some tests spell out each step, where clippy would rather see them compacted.
+#[allow(clippy::vec_init_then_push, clippy::drop_non_drop)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finish_writes_remaining_bits() {
+        let mut writer = LittleEndianWriter::new(vec![]);
+        writer.write(0x1ff, 9).unwrap();
+        assert_eq!(writer.get_ref(), &[0xff]);
+        assert_eq!(writer.finish().unwrap(), [0xff, 0x01]);
+
+        let mut writer = BigEndianWriter::new(vec![]);
+        writer.write(0x1ff, 9).unwrap();
+        assert_eq!(writer.get_ref(), &[0xff]);
+        assert_eq!(writer.finish().unwrap(), [0xff, 0x80]);
+    }
+
+    #[test]
+    fn reader_accessors() {
+        let input = [0x12, 0x34];
+
+        let mut reader = LittleEndianReader::new(&input[..]);
+        assert_eq!(reader.get_ref().len(), 2);
+        assert_eq!(reader.read_one(8).unwrap(), 0x12);
+        // Everything was fetched ahead.
+        assert!(reader.get_mut().is_empty());
+
+        let reader = BigEndianReader::new(&input[..]);
+        assert_eq!(reader.get_ref(), &input);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn remaining_hint_of_slices() {
+        let data = [0u8; 100];
+        let mut slice = &data[10..];
+
+        assert_eq!(remaining_hint(&mut slice), 90);
+        assert_eq!(remaining_hint(&mut std::io::BufReader::new(slice)), 90);
+        assert_eq!(remaining_hint(&mut std::io::empty()), 0);
+        // Only a hint: nothing was read.
+        assert_eq!(slice.len(), 90);
+    }
+
+    #[test]
+    fn bytes_consumed_counts_partly_read_bytes() {
+        let data = [0xAB; 100];
+
+        let mut reader = LittleEndianReader::new(&data[..]);
+        assert_eq!(reader.bytes_consumed(), 0);
+        reader.read_one(3).unwrap();
+        assert_eq!(reader.bytes_consumed(), 1);
+        reader.read_one(5).unwrap();
+        assert_eq!(reader.bytes_consumed(), 1);
+        reader.read_one(9).unwrap();
+        assert_eq!(reader.bytes_consumed(), 3);
+        reader.read(16, &mut [0; 10]).unwrap();
+        assert_eq!(reader.bytes_consumed(), 23);
+
+        let mut reader = BigEndianReader::new(&data[..]);
+        reader.read_one(12).unwrap();
+        assert_eq!(reader.bytes_consumed(), 2);
+        reader.skip_to_byte_boundary();
+        assert_eq!(reader.bytes_consumed(), 2);
+        reader.read_one(1).unwrap();
+        assert_eq!(reader.bytes_consumed(), 3);
+        reader.reset(&data[..]);
+        assert_eq!(reader.bytes_consumed(), 0);
+    }
+
+    #[test]
+    fn into_parts_hands_back_the_bytes_not_consumed() {
+        let data: Vec<u8> = (0..100).collect();
+
+        for amount in [3, 12, 16] {
+            let mut reader = LittleEndianReader::new(&data[..]);
+            reader.read_one(amount).unwrap();
+            let consumed = reader.bytes_consumed() as usize;
+            let (rest, read_ahead) = reader.into_parts();
+            assert_eq!([&read_ahead[..], rest].concat(), data[consumed..]);
+
+            let mut reader = BigEndianReader::new(&data[..]);
+            reader.read_one(amount).unwrap();
+            let consumed = reader.bytes_consumed() as usize;
+            let (rest, read_ahead) = reader.into_parts();
+            assert_eq!([&read_ahead[..], rest].concat(), data[consumed..]);
+        }
+    }
+
+    #[test]
+    fn pack_codes_round_trip() {
+        // Widths growing as a dictionary would.
+        let widths: Vec<u8> = (0..500).map(|i| 9 + (i / 100) as u8).collect();
+        let codes: Vec<u16> = widths
+            .iter()
+            .enumerate()
+            .map(|(i, &width)| (i * 7919) as u16 & ((1 << width) - 1))
+            .collect();
+
+        for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let mut packed = vec![];
+            pack_codes(&codes, &widths, &mut packed, endianness).unwrap();
+
+            let bits: usize = widths.iter().map(|&width| width as usize).sum();
+            assert_eq!(packed.len(), bits.div_ceil(8));
+            assert_eq!(
+                unpack_codes(&packed[..], &widths, endianness).unwrap(),
+                codes
+            );
+        }
+    }
+
+    #[test]
+    fn pack_codes_invalid_widths() {
+        let mut packed = vec![];
+
+        let error = pack_codes(&[1, 2], &[9], &mut packed, Endianness::LittleEndian).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        let error = pack_codes(&[1, 2], &[9, 17], &mut packed, Endianness::BigEndian).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert!(packed.is_empty());
+
+        let error = unpack_codes(&[0xff][..], &[9], Endianness::LittleEndian).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn reset_reads_like_a_new_reader() {
+        let first = [0xAB, 0xCD, 0xEF];
+        let second: Vec<u8> = (0..100).collect();
+
+        let mut reader = LittleEndianReader::new(&first[..]);
+        assert_eq!(reader.read_one(5).unwrap(), 0x0B);
+        reader.reset(&second[..]);
+        let reused: Vec<u16> = reader.iter(9).map(Result::unwrap).collect();
+        let fresh: Vec<u16> = LittleEndianReader::new(&second[..])
+            .iter(9)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(reused, fresh);
+
+        let mut reader = BigEndianReader::new(&first[..]);
+        assert_eq!(reader.read_one(5).unwrap(), 0x15);
+        reader.reset(&second[..]);
+        let reused: Vec<u16> = reader.iter(9).map(Result::unwrap).collect();
+        let fresh: Vec<u16> = BigEndianReader::new(&second[..])
+            .iter(9)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(reused, fresh);
     }
 
-    #[inline]
-    fn fill(&mut self) -> Result<(), std::io::Error> {
-        if self.cursor > 0 {
-            self.write.write_all(&[self.byte_buffer as u8])?;
-            self.byte_buffer = 0;
-            self.cursor = 0;
+    #[test]
+    fn write_many_matches_sequential_writes() -> Result<(), Error> {
+        let codes: Vec<u16> = (0..100).map(|i: u16| i.wrapping_mul(4099)).collect();
+        // Runs of codes of the same width, starting at every bit offset within a byte.
+        let runs = [(0, 3), (9, 10), (1, 7), (16, 20), (12, 5), (0, 0), (13, 55)];
+
+        let mut sequential_little = LittleEndianWriter::new(vec![]);
+        let mut sequential_big = BigEndianWriter::new(vec![]);
+        let mut many_little = LittleEndianWriter::new(vec![]);
+        let mut many_big = BigEndianWriter::new(vec![]);
+        let mut start = 0;
+        for (amount, count) in runs {
+            let run = &codes[start..start + count];
+            start += count;
+            for &code in run {
+                sequential_little.write(code, amount)?;
+                sequential_big.write(code, amount)?;
+            }
+            many_little.write_many(run, amount)?;
+            many_big.write_many(run, amount)?;
         }
+        assert_eq!(many_little.finish()?, sequential_little.finish()?);
+        assert_eq!(many_big.finish()?, sequential_big.finish()?);
+
+        // The default implementation, for the other writers.
+        let mut written = vec![];
+        CodeWriter::new(&mut written).write_many(&codes[..5], 16)?;
+        assert_eq!(written, codes[..5]);
+
+        let mut writer = LittleEndianWriter::new(vec![]);
+        let error = writer.write_many(&codes, 17).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert!(writer.finish()?.is_empty());
 
         Ok(())
     }
 
-    #[inline]
-    fn flush(&mut self) -> Result<(), std::io::Error> {
-        self.write.flush()
+    #[test]
+    fn reset_writes_like_a_new_writer() {
+        let mut writer = LittleEndianWriter::new(vec![]);
+        writer.write(0x1F, 5).unwrap();
+        writer.reset(vec![]);
+        writer.write(0x123, 9).unwrap();
+        assert_eq!(writer.finish().unwrap(), [0x23, 0x01]);
+
+        let mut writer = BigEndianWriter::new(vec![]);
+        writer.write(0x1F, 5).unwrap();
+        writer.reset(vec![]);
+        writer.write(0x123, 9).unwrap();
+        assert_eq!(writer.finish().unwrap(), [0x91, 0x80]);
     }
-}
-
-pub struct BigEndianWriter<W>
-where
-    W: Write,
-{
-    write: W,
-    cursor: u8,
-    byte_buffer: u32,
-}
 
-impl<W> BigEndianWriter<W>
-where
-    W: Write,
-{
-    pub fn new(write: W) -> Self {
-        let byte_buffer = 0;
-        let cursor = 0;
-        Self {
-            write,
-            byte_buffer,
-            cursor,
+    #[test]
+    fn default_reads_and_writes_nothing_until_reset() {
+        let data: Vec<u8> = (0..100).collect();
+        let fresh: Vec<u16> = LittleEndianReader::new(&data[..])
+            .iter(9)
+            .map(Result::unwrap)
+            .collect();
+
+        let mut reader = LittleEndianReader::<&[u8]>::default();
+        assert_eq!(reader.read_one_or_end(9).unwrap(), None);
+        reader.reset(&data[..]);
+        let reused: Vec<u16> = reader.iter(9).map(Result::unwrap).collect();
+        assert_eq!(reused, fresh);
+
+        let mut reader = BigEndianReader::<&[u8]>::default();
+        reader.reset(&[0x91, 0x80][..]);
+        assert_eq!(reader.read_one(9).unwrap(), 0x123);
+
+        let mut writer = LittleEndianWriter::<Vec<u8>>::default();
+        writer.reset(vec![]);
+        writer.write(0x123, 9).unwrap();
+        assert_eq!(writer.finish().unwrap(), [0x23, 0x01]);
+
+        let mut writer = BigEndianWriter::<Vec<u8>>::default();
+        writer.write(0x123, 9).unwrap();
+        assert_eq!(writer.finish().unwrap(), [0x91, 0x80]);
+
+        #[cfg(feature = "std")]
+        {
+            let mut reader = LittleEndianReader::<std::io::Empty>::default();
+            assert_eq!(reader.read_one_or_end(9).unwrap(), None);
+            let mut writer = BigEndianWriter::<std::io::Sink>::default();
+            writer.write(0x123, 9).unwrap();
+            writer.fill().unwrap();
         }
     }
-}
-
-impl<W> BitWriter for BigEndianWriter<W>
-where
-    W: Write,
-{
-    #[inline]
-    fn write(&mut self, data: u16, amount: u8) -> Result<(), std::io::Error> {
-        let mask = (1 << amount) - 1;
-        let shift = 32 - amount - self.cursor;
-        self.byte_buffer |= (data as u32 & mask) << shift;
-        self.cursor += amount;
 
-        while self.cursor >= 8 {
-            let byte = (self.byte_buffer >> 24) as u8;
-            self.byte_buffer <<= 8;
-            self.cursor -= 8;
+    #[test]
+    fn counting_writer_counts_what_the_inner_writer_got() {
+        let mut writer = CountingWriter::new(vec![]);
+        writer.write_all(&[1, 2, 3]).unwrap();
+        writer.write_all(&[]).unwrap();
+        writer.write_all(&[4, 5]).unwrap();
+        assert_eq!(writer.bytes_written(), 5);
+        assert_eq!(writer.into_inner(), [1, 2, 3, 4, 5]);
+
+        // Only the bytes fitting in the slice reach it.
+        let mut buffer = [0; 4];
+        let mut writer = CountingWriter::new(&mut buffer[..]);
+        assert!(writer.write_all(&[1, 2, 3, 4, 5, 6]).is_err());
+        assert_eq!(writer.bytes_written(), 4);
+        assert!(writer.get_ref().is_empty());
+        assert_eq!(buffer, [1, 2, 3, 4]);
+    }
 
-            self.write.write_all(&[byte])?;
-        }
+    #[test]
+    fn skip_to_byte_boundary_big_endian() {
+        let input = [0b1010_0000, 0b1100_0011];
 
-        Ok(())
+        let mut reader = BigEndianReader::new(&input[..]);
+        assert_eq!(0b101, reader.read_one(3).unwrap());
+        reader.skip_to_byte_boundary();
+        assert!(reader.has_remaining_data().unwrap());
+        assert_eq!(0b1100, reader.read_one(4).unwrap());
+        reader.skip_to_byte_boundary();
+        assert!(!reader.has_remaining_data().unwrap());
+
+        // Already on a boundary, nothing is skipped.
+        let mut reader = BigEndianReader::new(&input[..]);
+        reader.skip_to_byte_boundary();
+        assert_eq!(0b1010_0000, reader.read_one(8).unwrap());
     }
 
-    #[inline]
-    fn fill(&mut self) -> Result<(), std::io::Error> {
-        if self.cursor > 0 {
-            self.write.write_all(&[(self.byte_buffer >> 24) as u8])?;
-            self.byte_buffer = 0;
-            self.cursor = 0;
-        }
+    #[cfg(feature = "std")]
+    #[test]
+    fn horizontal_differencing() {
+        // Two rows of two RGB pixels.
+        let data = [10, 20, 30, 11, 25, 29, 100, 0, 255, 90, 1, 0];
+        let mut reader = DifferencingReader::new(&data[..], 6, 3).unwrap();
+        let mut differenced = vec![];
+        reader.read_to_end(&mut differenced).unwrap();
 
-        Ok(())
-    }
+        assert_eq!(differenced, [10, 20, 30, 1, 5, 255, 100, 0, 255, 246, 1, 1]);
 
-    #[inline]
-    fn flush(&mut self) -> Result<(), std::io::Error> {
-        self.write.flush()
+        let mut undifferenced = vec![];
+        let mut writer = UndifferencingWriter::new(&mut undifferenced, 6, 3).unwrap();
+        writer.write_all(&differenced[..5]).unwrap();
+        writer.write_all(&differenced[5..]).unwrap();
+
+        assert_eq!(undifferenced, data);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn horizontal_differencing_invalid_arguments() {
+        assert!(DifferencingReader::new(&[0u8][..], 0, 3).is_err());
+        assert!(UndifferencingWriter::new(vec![], 6, 0).is_err());
+    }
 
     #[test]
     fn read_1_little_endian() {
@@ -418,7 +2030,7 @@ mod tests {
     }
 
     #[test]
-    fn write_1_little_endian() -> Result<(), std::io::Error> {
+    fn write_1_little_endian() -> Result<(), Error> {
         let mut output = vec![];
 
         let mut writer = LittleEndianWriter::new(&mut output);
@@ -431,7 +2043,7 @@ mod tests {
     }
 
     #[test]
-    fn write_colors_little_endian() -> Result<(), std::io::Error> {
+    fn write_colors_little_endian() -> Result<(), Error> {
         let mut output = vec![];
 
         let mut writer = LittleEndianWriter::new(&mut output);
@@ -448,7 +2060,7 @@ mod tests {
     }
 
     #[test]
-    fn write_12bits_little_endian() -> Result<(), std::io::Error> {
+    fn write_12bits_little_endian() -> Result<(), Error> {
         let mut output = vec![];
 
         let mut writer = LittleEndianWriter::new(&mut output);
@@ -461,7 +2073,7 @@ mod tests {
     }
 
     #[test]
-    fn write_0xfffa_little_endian() -> Result<(), std::io::Error> {
+    fn write_0xfffa_little_endian() -> Result<(), Error> {
         let mut output = vec![];
 
         let mut writer = LittleEndianWriter::new(&mut output);
@@ -474,7 +2086,7 @@ mod tests {
     }
 
     #[test]
-    fn write_1_big_endian() -> Result<(), std::io::Error> {
+    fn write_1_big_endian() -> Result<(), Error> {
         let mut output = vec![];
 
         let mut writer = BigEndianWriter::new(&mut output);
@@ -487,7 +2099,7 @@ mod tests {
     }
 
     #[test]
-    fn write_colors_big_endian() -> Result<(), std::io::Error> {
+    fn write_colors_big_endian() -> Result<(), Error> {
         let mut output = vec![];
 
         let mut writer = BigEndianWriter::new(&mut output);
@@ -504,7 +2116,7 @@ mod tests {
     }
 
     #[test]
-    fn write_12bits_big_endian() -> Result<(), std::io::Error> {
+    fn write_12bits_big_endian() -> Result<(), Error> {
         let mut output = vec![];
 
         let mut writer = BigEndianWriter::new(&mut output);
@@ -517,7 +2129,7 @@ mod tests {
     }
 
     #[test]
-    fn write_0xfffa_big_endian() -> Result<(), std::io::Error> {
+    fn write_0xfffa_big_endian() -> Result<(), Error> {
         let mut output = vec![];
 
         let mut writer = BigEndianWriter::new(&mut output);
@@ -531,7 +2143,7 @@ mod tests {
     }
 
     #[test]
-    fn read_full_little_endian() -> Result<(), std::io::Error> {
+    fn read_full_little_endian() -> Result<(), Error> {
         let mut output = vec![];
         let mut writer = LittleEndianWriter::new(&mut output);
         writer.write(0, 12)?;
@@ -551,7 +2163,7 @@ mod tests {
     }
 
     #[test]
-    fn read_full_big_endian() -> Result<(), std::io::Error> {
+    fn read_full_big_endian() -> Result<(), Error> {
         let mut output = vec![];
         let mut writer = BigEndianWriter::new(&mut output);
         writer.write(0, 12)?;
@@ -569,4 +2181,496 @@ mod tests {
 
         Ok(())
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn buffered_readers_read_like_slices() -> Result<(), Error> {
+        let codes = (0..500).map(|code| code * 7 % 4096).collect::<Vec<u16>>();
+        let widths = vec![12; codes.len()];
+
+        for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let mut data = vec![];
+            pack_codes(&codes, &widths, &mut data, endianness)?;
+
+            // Tiny buffers, so that codes keep straddling their boundaries.
+            for capacity in [1, 3, 64, 100] {
+                let buffered = std::io::BufReader::with_capacity(capacity, &data[..]);
+                let read: Result<Vec<u16>, _> = match endianness {
+                    Endianness::LittleEndian => {
+                        LittleEndianReader::new(buffered).iter(12).collect()
+                    }
+                    Endianness::BigEndian => BigEndianReader::new(buffered).iter(12).collect(),
+                };
+                assert_eq!(read?, codes, "{endianness:?}, capacity {capacity}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_sub_blocks() -> Result<(), Error> {
+        let data: Vec<u8> = (0..=255).cycle().take(600).collect();
+
+        let mut writer = SubBlockWriter::new(vec![]);
+        writer.write_all(&data)?;
+        let output = writer.finish()?;
+
+        assert_eq!(output.len(), 600 + 3 + 1);
+        assert_eq!(output[0], 255);
+        assert_eq!(&output[1..256], &data[..255]);
+        assert_eq!(output[256], 255);
+        assert_eq!(&output[257..512], &data[255..510]);
+        assert_eq!(output[512], 90);
+        assert_eq!(&output[513..603], &data[510..]);
+        assert_eq!(output[603], 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_no_sub_blocks() -> Result<(), Error> {
+        let writer = SubBlockWriter::new(vec![]);
+        let output = writer.finish()?;
+
+        assert_eq!(output, [0]);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn read_sub_blocks() -> Result<(), Error> {
+        let input = [3, 1, 2, 3, 1, 4, 2, 5, 6, 0, 42];
+
+        let mut read = &input[..];
+        let mut reader = SubBlockReader::new(&mut read);
+        let mut output = vec![];
+        reader.read_to_end(&mut output)?;
+
+        assert_eq!(output, [1, 2, 3, 4, 5, 6]);
+        assert_eq!(read, [42]);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn read_sub_blocks_without_terminator() {
+        let input = [3, 1, 2, 3, 1, 4];
+
+        let mut reader = SubBlockReader::new(&input[..]);
+        let mut output = vec![];
+        let error = reader.read_to_end(&mut output).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn read_truncated_sub_block() {
+        let input = [3, 1, 2];
+
+        let mut reader = SubBlockReader::new(&input[..]);
+        let mut output = vec![];
+        let error = reader.read_to_end(&mut output).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn read_zero_bits() -> Result<(), Error> {
+        let input = [0xfa];
+
+        let mut reader = LittleEndianReader::new(&input[..]);
+        assert_eq!(reader.read_one(0)?, 0);
+        assert_eq!(reader.read_one(8)?, 0xfa);
+
+        let mut reader = BigEndianReader::new(&input[..]);
+        let mut buf = [1; 2];
+        assert_eq!(reader.read(0, &mut buf)?, 2);
+        assert_eq!(buf, [0, 0]);
+        assert_eq!(reader.read_one(8)?, 0xfa);
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_too_many_bits() {
+        let input = [0xff, 0xff, 0xff];
+
+        let mut reader = LittleEndianReader::new(&input[..]);
+        let error = reader.read_one(17).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+        let mut reader = BigEndianReader::new(&input[..]);
+        let error = reader.read(17, &mut [0; 1]).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn write_zero_bits() -> Result<(), Error> {
+        let mut output = vec![];
+        let mut writer = LittleEndianWriter::new(&mut output);
+        writer.write(0xffff, 0)?;
+        writer.write(0x1, 1)?;
+        writer.write(0xffff, 0)?;
+        writer.fill()?;
+        assert_eq!(output, [0x01]);
+
+        let mut output = vec![];
+        let mut writer = BigEndianWriter::new(&mut output);
+        writer.write(0xffff, 0)?;
+        writer.write(0x1, 1)?;
+        writer.write(0xffff, 0)?;
+        writer.fill()?;
+        assert_eq!(output, [0x80]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_too_many_bits() {
+        let mut writer = LittleEndianWriter::new(vec![]);
+        let error = writer.write(0xffff, 17).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+        let mut writer = BigEndianWriter::new(vec![]);
+        writer.write(0x7f, 7).unwrap();
+        let error = writer.write(0xffff, 255).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn write_16_bits_after_7_big_endian() -> Result<(), Error> {
+        let mut output = vec![];
+        let mut writer = BigEndianWriter::new(&mut output);
+        writer.write(0x7f, 7)?;
+        writer.write(0xffff, 16)?;
+        writer.fill()?;
+
+        assert_eq!(output, [0xff, 0xff, 0xfe]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fill_with_pads_the_last_byte() -> Result<(), Error> {
+        for (bit, little_expected, big_expected) in [
+            (false, [0xff, 0x05], [0xff, 0xa0]),
+            (true, [0xff, 0xfd], [0xff, 0xbf]),
+        ] {
+            let mut writer = LittleEndianWriter::new(vec![]);
+            writer.write(0x5ff, 11)?;
+            writer.fill_with(bit)?;
+            assert_eq!(writer.into_inner(), little_expected, "{bit}");
+
+            let mut writer = BigEndianWriter::new(vec![]);
+            writer.write(0x7fd, 11)?;
+            writer.fill_with(bit)?;
+            assert_eq!(writer.into_inner(), big_expected, "{bit}");
+        }
+
+        // Nothing to pad on a byte boundary.
+        let mut writer = LittleEndianWriter::new(vec![]);
+        writer.write(0xab, 8)?;
+        writer.fill_with(true)?;
+        assert_eq!(writer.into_inner(), [0xab]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn filled_segments_concatenate() -> Result<(), crate::decoder::DecodingError> {
+        use crate::{decoder::VariableDecoder, encoder::VariableEncoder, CodeSizeStrategy};
+
+        let segments: [&[u8]; 2] = [&[0, 0, 1, 3], &[2, 2, 2, 1, 0]];
+
+        let mut writer = BigEndianWriter::new(vec![]);
+        assert!(writer.is_byte_aligned());
+        writer.write(0x1, 3)?;
+        assert!(!writer.is_byte_aligned());
+        writer.fill()?;
+        assert!(writer.is_byte_aligned());
+
+        for segment in segments {
+            VariableEncoder::encode_with_writer(
+                segment,
+                &mut writer,
+                2,
+                12,
+                CodeSizeStrategy::Tiff,
+            )
+            .unwrap();
+            assert!(writer.is_byte_aligned());
+        }
+        let stream = writer.into_inner();
+        assert_eq!(stream[0], 0x20);
+
+        let mut rest = &stream[1..];
+        for segment in segments {
+            let mut decoded = vec![];
+            let consumed = VariableDecoder::decode_counted(
+                rest,
+                &mut decoded,
+                2,
+                12,
+                crate::Endianness::BigEndian,
+                CodeSizeStrategy::Tiff,
+            )?;
+            assert_eq!(decoded, segment);
+            rest = &rest[consumed as usize..];
+        }
+        assert!(rest.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn writers_write_in_bulk() -> Result<(), Error> {
+        let values: Vec<(u16, u8)> = (0..10).flat_map(|_| values_across_64_bits()).collect();
+
+        let mut writer = LittleEndianWriter::buffered(WriteCalls(vec![], 0));
+        for &(value, amount) in &values {
+            writer.write(value, amount)?;
+        }
+        let WriteCalls(little, little_calls) = writer.finish()?;
+
+        let mut writer = BigEndianWriter::buffered(WriteCalls(vec![], 0));
+        for &(value, amount) in &values {
+            writer.write(value, amount)?;
+        }
+        let WriteCalls(big, big_calls) = writer.finish()?;
+
+        for calls in [little_calls, big_calls] {
+            assert_eq!(calls, little.len().div_ceil(WRITE_BEHIND_SIZE));
+        }
+
+        let mut little_reader = LittleEndianReader::new(&little[..]);
+        let mut big_reader = BigEndianReader::new(&big[..]);
+        for &(value, amount) in &values {
+            assert_eq!(little_reader.read_one(amount)?, value);
+            assert_eq!(big_reader.read_one(amount)?, value);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn buffered_writers_hold_bytes_back() -> Result<(), Error> {
+        let mut writer = LittleEndianWriter::buffered(vec![]);
+        writer.write(0x1ff, 9)?;
+        assert!(writer.get_ref().is_empty());
+        writer.flush()?;
+        assert_eq!(writer.get_ref(), &[0xff]);
+        assert_eq!(writer.finish()?, [0xff, 0x01]);
+
+        let mut writer = BigEndianWriter::buffered(vec![]);
+        writer.write(0x1ff, 9)?;
+        assert!(writer.get_ref().is_empty());
+        writer.flush()?;
+        assert_eq!(writer.get_ref(), &[0xff]);
+        assert_eq!(writer.finish()?, [0xff, 0x80]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn buffered_writers_fail_on_a_full_output() {
+        let mut output = [0u8; 10];
+        let mut writer = LittleEndianWriter::buffered(&mut output[..]);
+        let error = (0..100).try_for_each(|_| writer.write(1, 8)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::WriteZero);
+        assert!(writer.write(1, 8).is_err());
+        assert!(writer.fill().is_err());
+
+        let mut output = [0u8; 10];
+        let mut writer = BigEndianWriter::buffered(&mut output[..]);
+        let error = (0..100).try_for_each(|_| writer.write(1, 8)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::WriteZero);
+        assert!(writer.write(1, 8).is_err());
+        assert!(writer.fill().is_err());
+    }
+
+    /// Counts the calls to `write`, along with the bytes written.
+    struct WriteCalls(Vec<u8>, usize);
+
+    impl Write for WriteCalls {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+            self.1 += 1;
+            self.0.write(buf)
+        }
+
+        fn flush(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    /// Hands out a single byte per call to `read`, so the bit buffer only gets partial refills.
+    struct ByteByByte<'a>(&'a [u8]);
+
+    impl Read for ByteByByte<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+            let len = buf.len().min(1);
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    /// Reads values of `amount` bits until the data ends, returning them along with whether
+    /// the data ended cleanly rather than in the middle of a value.
+    fn read_to_end<B: BitReader>(reader: &mut B, amount: u8) -> (Vec<u16>, bool) {
+        let mut values = vec![];
+        loop {
+            match reader.read_one_or_end(amount) {
+                Ok(Some(value)) => values.push(value),
+                Ok(None) => return (values, true),
+                Err(error) => {
+                    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+                    // The bits left never make it into a value, however the reader is asked.
+                    assert!(reader.read_one(amount).is_err());
+                    assert_eq!(reader.read(amount, &mut [0; 4]).unwrap(), 0);
+                    return (values, false);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn wide_reads_near_the_end() {
+        let bytes = [0xAB, 0xCD, 0xEF];
+        let little = u32::from_le_bytes([0xAB, 0xCD, 0xEF, 0]);
+        let big = u32::from_be_bytes([0xAB, 0xCD, 0xEF, 0]);
+
+        for amount in 9..=16 {
+            let width = usize::from(amount);
+            let mask = (1 << amount) - 1;
+            for length in 0..=bytes.len() {
+                let data = &bytes[..length];
+                let count = length * 8 / width;
+                // Less than a byte left after the whole values is only the padding.
+                let clean_end = length * 8 - count * width < 8;
+                let expected_little: Vec<u16> = (0..count)
+                    .map(|index| ((little >> (index * width)) & mask) as u16)
+                    .collect();
+                let expected_big: Vec<u16> = (0..count)
+                    .map(|index| ((big >> (32 - (index + 1) * width)) & mask) as u16)
+                    .collect();
+                let expected_little = (expected_little, clean_end);
+                let expected_big = (expected_big, clean_end);
+
+                let context = format!("{amount} bits from {length} bytes");
+                let mut reader = LittleEndianReader::new(data);
+                assert_eq!(
+                    read_to_end(&mut reader, amount),
+                    expected_little,
+                    "{context}"
+                );
+                let mut reader = LittleEndianReader::new(ByteByByte(data));
+                assert_eq!(
+                    read_to_end(&mut reader, amount),
+                    expected_little,
+                    "{context}"
+                );
+                let mut reader = BigEndianReader::new(data);
+                assert_eq!(read_to_end(&mut reader, amount), expected_big, "{context}");
+                let mut reader = BigEndianReader::new(ByteByByte(data));
+                assert_eq!(read_to_end(&mut reader, amount), expected_big, "{context}");
+
+                let mut buf = [0; 4];
+                let read = LittleEndianReader::new(data)
+                    .read(amount, &mut buf)
+                    .unwrap();
+                assert_eq!(buf[..read], expected_little.0, "{context}");
+                let read = BigEndianReader::new(data).read(amount, &mut buf).unwrap();
+                assert_eq!(buf[..read], expected_big.0, "{context}");
+            }
+        }
+    }
+
+    /// Values of various sizes, several of them straddling the 64 bits of the bit buffer.
+    fn values_across_64_bits() -> Vec<(u16, u8)> {
+        let mut values = vec![
+            (0x7f, 7),
+            (0x1234, 16),
+            (0xffff, 16),
+            (0xabcd, 16),
+            (0x1ff, 9),
+        ];
+        values.extend((0..40).map(|i: u16| (i.wrapping_mul(397) & 0x1fff, 13)));
+        values.extend([(0x5, 3), (0xfffe, 16), (0x1, 1), (0xbeef, 16)]);
+        values
+    }
+
+    #[test]
+    fn read_across_64_bits_little_endian() -> Result<(), Error> {
+        let values = values_across_64_bits();
+        let mut writer = LittleEndianWriter::new(vec![]);
+        for &(value, amount) in &values {
+            writer.write(value, amount)?;
+        }
+        writer.fill()?;
+        let packed = writer.into_inner();
+
+        let mut reader = LittleEndianReader::new(&packed[..]);
+        let mut slow_reader = LittleEndianReader::new(ByteByByte(&packed[..]));
+        for &(value, amount) in &values {
+            assert_eq!(reader.read_one(amount)?, value);
+            assert_eq!(slow_reader.read_one(amount)?, value);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_across_64_bits_big_endian() -> Result<(), Error> {
+        let values = values_across_64_bits();
+        let mut writer = BigEndianWriter::new(vec![]);
+        for &(value, amount) in &values {
+            writer.write(value, amount)?;
+        }
+        writer.fill()?;
+        let packed = writer.into_inner();
+
+        let mut reader = BigEndianReader::new(&packed[..]);
+        let mut slow_reader = BigEndianReader::new(ByteByByte(&packed[..]));
+        for &(value, amount) in &values {
+            assert_eq!(reader.read_one(amount)?, value);
+            assert_eq!(slow_reader.read_one(amount)?, value);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_exactly_64_bits() -> Result<(), Error> {
+        let input = [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x42];
+
+        let mut output = [0; 5];
+        let mut reader = LittleEndianReader::new(&input[..]);
+        assert_eq!(reader.read(16, &mut output)?, 4);
+        assert_eq!(output[..4], [0x2301, 0x6745, 0xab89, 0xefcd]);
+        assert_eq!(reader.read_one(8)?, 0x42);
+
+        let mut reader = BigEndianReader::new(&input[..]);
+        assert_eq!(reader.read(16, &mut output)?, 4);
+        assert_eq!(output[..4], [0x0123, 0x4567, 0x89ab, 0xcdef]);
+        assert_eq!(reader.read_one(8)?, 0x42);
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_one_past_the_end() {
+        let input = [0xff, 0xff, 0xff];
+
+        let mut reader = LittleEndianReader::new(&input[..]);
+        assert_eq!(reader.read_one(16).unwrap(), 0xffff);
+        let error = reader.read_one(9).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+
+        let mut reader = BigEndianReader::new(&input[..]);
+        assert_eq!(reader.read_one(16).unwrap(), 0xffff);
+        let error = reader.read_one(9).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
 }
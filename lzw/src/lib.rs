@@ -4,9 +4,24 @@
 //! This particular implementation provides the GIF and TIFF variation, as well
 //! as the original fixed 12 bit LZW variation.
 //!
-//! It's fast, and use limited memory to do so: the decoder only uses the stack.
+//! It's fast, and use limited memory to do so: for codes up to 12 bits, the decoder only uses
+//! the stack.
 //!
-//! It works with any [std::io::Read] and [std::io::Write].
+//! For a quick start, [compress] and [decompress] work on byte slices with GIF style LZW
+//! and a code size of 8.
+//!
+//! It works with any [Read](io::Read) and [Write](io::Write), the `std::io` ones unless the
+//! `std` feature is turned off. The bit packing used under the hood is available on its own
+//! in the [io] module.
+//!
+//! # `no_std` support
+//!
+//! The `std` feature is on by default. Without it, the crate only needs `core` and `alloc`,
+//! for instance to decode GIF images on embedded targets. Everything stays available but the
+//! [Read](io::Read) adapters of the `reader` module, with a few differences:
+//! * [io::Read], [io::Write] and [io::Error] are minimal stand-ins for the `std::io` ones,
+//!   implemented for byte slices and `Vec<u8>`.
+//! * The decoders write each decoded word right away, instead of buffering their output.
 //!
 //! # Examples
 //!
@@ -48,15 +63,32 @@
 //! TiffStyleEncoder::encode(data, output_file).expect("Compression failed");
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+// The tests print and use `std` helpers, whichever way the crate is built.
+#[cfg(all(test, not(feature = "std")))]
+#[macro_use]
+extern crate std;
+
+#[cfg(not(feature = "std"))]
+mod core_io;
 pub mod decoder;
 pub mod encoder;
-mod io;
+pub mod gif;
+pub mod io;
+pub mod prelude;
+#[cfg(feature = "std")]
+pub mod reader;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod transcoder;
 
 /// The bit ordering when encoding or decoding LZW.
 ///
 /// This crate currently only supports the GIF variation and GIF typically use little endian,
 /// but big endian still works.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Endianness {
     /// Most significant order.
     BigEndian,
@@ -68,7 +100,7 @@ pub enum Endianness {
 ///
 /// For variable code size encoding, there is a difference between the strategy used
 /// by TIFF compared to GIF or other variable code LZW.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CodeSizeStrategy {
     /// Default code size increase.
     ///
@@ -81,6 +113,259 @@ pub enum CodeSizeStrategy {
     Tiff,
 }
 
+/// What to do once the dictionary is full, for variable code size encoding.
+///
+/// Decoders are told the policy the data was encoded with, as it changes which codes are valid
+/// past that point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DictionaryPolicy {
+    /// Write a clear code and start over with a fresh dictionary, like GIF and TIFF do.
+    ///
+    /// When decoding, a full dictionary must be followed by a clear code: any other code
+    /// fails with [decoder::DecodingError::MissingClearCode].
+    Reset,
+    /// Stop growing the dictionary, and keep using the words it already holds.
+    /// No clear code is ever written.
+    ///
+    /// When decoding, the codes of a full dictionary keep being decoded with the words it
+    /// holds. Clear codes are still honored, for encoders that only write them from time to
+    /// time.
+    Freeze,
+}
+
+/// An initial code size, checked once to be between 1 and 8.
+///
+/// Code sizes are otherwise bare [u8]s, checked by each encode or decode. The builders only
+/// take a [CodeSize]: [encoder::EncoderBuilder::code_size] and
+/// [decoder::DecoderBuilder::code_size]. Check it with [CodeSize::new] when encoding, and
+/// with [CodeSize::for_decoding] when decoding, to get the matching error type.
+///
+/// GIF needs a code size of at least 2, and TIFF always uses 8.
+///
+/// # Examples
+/// ```
+/// use salzweg::{decoder::DecoderBuilder, encoder::EncoderBuilder, CodeSize};
+///
+/// let code_size = CodeSize::new(2).expect("Unsupported code size");
+/// let data = [0, 0, 1, 3];
+///
+/// let mut compressed = vec![];
+/// EncoderBuilder::gif(code_size)
+///     .build()
+///     .and_then(|encoder| encoder.encode(&data[..], &mut compressed))
+///     .expect("Compression failed");
+///
+/// let mut decompressed = vec![];
+/// DecoderBuilder::gif(code_size)
+///     .decode(&compressed[..], &mut decompressed)
+///     .expect("Decompression failed");
+///
+/// assert_eq!(decompressed, data);
+/// assert!(CodeSize::new(9).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CodeSize(u8);
+
+impl CodeSize {
+    /// Check that `code_size` is between 1 and 8.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [encoder::EncodingError::CodeSize] for any other code size.
+    pub const fn new(code_size: u8) -> Result<Self, encoder::EncodingError> {
+        if Self::is_valid(code_size) {
+            Ok(Self(code_size))
+        } else {
+            Err(encoder::EncodingError::CodeSize(code_size))
+        }
+    }
+
+    /// Check that `code_size` is between 1 and 8, like [CodeSize::new], but for decoding.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [decoder::DecodingError::CodeSize] for any other code size.
+    pub const fn for_decoding(code_size: u8) -> Result<Self, decoder::DecodingError> {
+        if Self::is_valid(code_size) {
+            Ok(Self(code_size))
+        } else {
+            Err(decoder::DecodingError::CodeSize(code_size))
+        }
+    }
+
+    const fn is_valid(code_size: u8) -> bool {
+        code_size >= 1 && code_size <= 8
+    }
+
+    /// The code size, between 1 and 8.
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for CodeSize {
+    type Error = encoder::EncodingError;
+
+    fn try_from(code_size: u8) -> Result<Self, Self::Error> {
+        Self::new(code_size)
+    }
+}
+
+impl From<CodeSize> for u8 {
+    fn from(code_size: CodeSize) -> Self {
+        code_size.0
+    }
+}
+
+/// Words known to both the encoder and the decoder before any data, like a zstd dictionary.
+///
+/// Many small and similar payloads compress poorly with LZW, as each starts from an empty
+/// dictionary. Seeding it with their common sequences lets even the first bytes use long
+/// words. Give it to [encoder::EncodingOptions::dictionary], and the very same dictionary to
+/// [decoder::DecodingOptions::dictionary].
+///
+/// Each word adds one entry per prefix the dictionary doesn't hold yet: `"abc"` adds `"ab"`,
+/// then `"abc"`. The entries follow the clear and end of information codes, and are added
+/// again after each clear code.
+///
+/// # Examples
+/// ```
+/// use salzweg::{
+///     decoder::{DecodingOptions, VariableDecoder},
+///     encoder::{EncodingOptions, VariableEncoder},
+///     Dictionary,
+/// };
+///
+/// let dictionary = Dictionary::new(["{\"id\": ", ", \"name\": \""]);
+/// let data = br#"{"id": 12, "name": "salzweg"}"#;
+///
+/// let mut compressed = vec![];
+/// VariableEncoder::encode_with_options(
+///     &data[..],
+///     &mut compressed,
+///     EncodingOptions {
+///         dictionary: Some(dictionary.clone()),
+///         ..Default::default()
+///     },
+/// )
+/// .expect("Compression failed");
+///
+/// let mut decompressed = vec![];
+/// VariableDecoder::decode_with_options(
+///     &compressed[..],
+///     &mut decompressed,
+///     DecodingOptions {
+///         dictionary: Some(dictionary),
+///         ..Default::default()
+///     },
+/// )
+/// .expect("Decompression failed");
+///
+/// assert_eq!(decompressed, data);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dictionary {
+    // Each entry is a shorter word followed by one more byte. The shorter word is a single
+    // byte below 256, and the entry numbered 256 less otherwise.
+    entries: alloc::vec::Vec<(usize, u8)>,
+}
+
+impl Dictionary {
+    /// Build a dictionary from its words. Empty words and single bytes add nothing.
+    pub fn new<I, W>(words: I) -> Self
+    where
+        I: IntoIterator<Item = W>,
+        W: AsRef<[u8]>,
+    {
+        let mut known = alloc::collections::BTreeMap::new();
+        let mut entries = alloc::vec::Vec::new();
+        for word in words {
+            if let Some((&first, rest)) = word.as_ref().split_first() {
+                let mut prefix = first as usize;
+                for &byte in rest {
+                    prefix = *known.entry((prefix, byte)).or_insert_with(|| {
+                        entries.push((prefix, byte));
+                        255 + entries.len()
+                    });
+                }
+            }
+        }
+
+        Self { entries }
+    }
+
+    /// How many entries the dictionary adds after the clear and end of information codes.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the dictionary adds no entry at all.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The entries as their prefix code and last byte, in order, for an initial dictionary of
+    /// `possibilities` single symbols. `None` if a byte is not one of the symbols, or if
+    /// the entries leave no room to grow before the code size maxes out.
+    pub(crate) fn codes(
+        &self,
+        possibilities: u16,
+        max_code_size: u8,
+        code_size_strategy: CodeSizeStrategy,
+    ) -> Option<alloc::vec::Vec<(u16, u8)>> {
+        let first_free_code = possibilities as usize + 2;
+        let last_entry = (1 << max_code_size) - code_size_strategy.increment() as usize;
+        if first_free_code + self.entries.len() >= last_entry {
+            return None;
+        }
+
+        self.entries
+            .iter()
+            .map(|&(prefix, byte)| {
+                if byte as u16 >= possibilities {
+                    None
+                } else if prefix < 256 {
+                    (prefix < possibilities as usize).then_some((prefix as u16, byte))
+                } else {
+                    Some(((first_free_code + prefix - 256) as u16, byte))
+                }
+            })
+            .collect()
+    }
+}
+
+/// The number of entries of the dictionary for codes of 12 bits, the widest codes used by GIF,
+/// TIFF and the fixed code LZW.
+///
+/// Up to this size, the decoders keep their tables on the stack.
+pub const MAX_TABLE_SIZE: usize = 4096;
+
+/// The length of the longest word a dictionary of [MAX_TABLE_SIZE] entries can hold, for variable
+/// code size LZW starting with the given code size.
+///
+/// The longest word would be reached if by bad luck, each entry of the dictionary is made of the
+/// previous entry, increasing in size each time: one of the 2^code_size single bytes, plus all
+/// the entries left once the clear and end of information codes are taken.
+/// In effect, max word length = table size - 2^code_size - 2 + 1.
+///
+/// Decoding a single code never outputs more bytes than that, which helps sizing buffers.
+///
+/// # Examples
+/// ```
+/// // Bilevel data, using a code size of 1.
+/// assert_eq!(salzweg::max_word_length(1), 4093);
+/// // GIF data using a code size of 2.
+/// assert_eq!(salzweg::max_word_length(2), 4091);
+/// // TIFF data, always starting with a code size of 8.
+/// assert_eq!(salzweg::max_word_length(8), 3839);
+/// ```
+pub const fn max_word_length(code_size: u8) -> usize {
+    match 1usize.checked_shl(code_size as u32) {
+        Some(single_bytes) => MAX_TABLE_SIZE.saturating_sub(single_bytes + 2) + 1,
+        None => 1,
+    }
+}
+
 impl CodeSizeStrategy {
     pub(crate) const fn increment(&self) -> u16 {
         match self {
@@ -89,3 +374,130 @@ impl CodeSizeStrategy {
         }
     }
 }
+
+/// The flavors of LZW, to pick one at run time, with [encode] and [decode].
+///
+/// # Examples
+/// ```
+/// use salzweg::{Endianness, Style};
+///
+/// let data = [0, 0, 1, 3];
+///
+/// for style in [
+///     Style::Gif { code_size: 2 },
+///     Style::Tiff,
+///     Style::Fixed {
+///         endianness: Endianness::BigEndian,
+///     },
+/// ] {
+///     let mut compressed = vec![];
+///     salzweg::encode(style, &data[..], &mut compressed).expect("Compression failed");
+///
+///     let mut decompressed = vec![];
+///     salzweg::decode(style, &compressed[..], &mut decompressed).expect("Decompression failed");
+///
+///     assert_eq!(decompressed, data);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Style {
+    /// GIF flavored LZW, see [encoder::GifStyleEncoder] and [decoder::GifStyleDecoder].
+    Gif {
+        /// Between 2 and 8, the initial code size to use.
+        code_size: u8,
+    },
+    /// TIFF flavored LZW, see [encoder::TiffStyleEncoder] and [decoder::TiffStyleDecoder].
+    Tiff,
+    /// LZW with fixed codes of 12 bits, see [encoder::FixedEncoder] and [decoder::FixedDecoder].
+    Fixed {
+        /// Bit ordering of the compressed data.
+        endianness: Endianness,
+    },
+}
+
+/// Compress data with LZW, in the given [Style].
+///
+/// # Arguments
+///
+/// * `style` - The flavor of LZW to use.
+/// * `data` - The source data to be compressed.
+/// * `into` - The output where compressed data should be written.
+///
+/// # Errors
+///
+/// This function fails as the encoder of the style would.
+pub fn encode<R: io::Read, W: io::Write>(
+    style: Style,
+    data: R,
+    into: W,
+) -> Result<(), encoder::EncodingError> {
+    match style {
+        Style::Gif { code_size } => encoder::GifStyleEncoder::encode(data, into, code_size),
+        Style::Tiff => encoder::TiffStyleEncoder::encode(data, into),
+        Style::Fixed { endianness } => encoder::FixedEncoder::encode(data, into, endianness),
+    }
+}
+
+/// Decompress LZW data, in the given [Style].
+///
+/// Except for [Style::Fixed], which reads until the end, the data is read ahead past the end of
+/// information code: see [reading ahead](crate::decoder#reading-ahead).
+///
+/// # Arguments
+///
+/// * `style` - The flavor of LZW the data was compressed with.
+/// * `data` - The source data to be decoded.
+/// * `into` - The output where decoded data will be written.
+///
+/// # Errors
+///
+/// This function fails as the decoder of the style would.
+pub fn decode<R: io::Read, W: io::Write>(
+    style: Style,
+    data: R,
+    into: W,
+) -> Result<(), decoder::DecodingError> {
+    match style {
+        Style::Gif { code_size } => decoder::GifStyleDecoder::decode(data, into, code_size),
+        Style::Tiff => decoder::TiffStyleDecoder::decode(data, into),
+        Style::Fixed { endianness } => decoder::FixedDecoder::decode(data, into, endianness),
+    }
+}
+
+/// Compress `data` with LZW, GIF style, with a code size of 8: any byte goes.
+///
+/// The shortest way in: the [encoder] module has the other flavors and settings. The result
+/// decompresses with [decompress].
+///
+/// # Examples
+/// ```
+/// let data = b"TOBEORNOTTOBEORTOBEORNOT";
+///
+/// let compressed = salzweg::compress(data);
+/// let decompressed = salzweg::decompress(&compressed).expect("Decompression failed");
+///
+/// assert_eq!(decompressed, data);
+/// ```
+pub fn compress(data: &[u8]) -> alloc::vec::Vec<u8> {
+    // With a valid code size, all bytes are valid data, and writing to a vector can't fail.
+    encoder::GifStyleEncoder::encode_to_vec(data, 8).expect("GIF encoding of bytes can't fail")
+}
+
+/// Decompress LZW data, as compressed by [compress]: GIF style, with a code size of 8.
+///
+/// The [decoder] module has the other flavors and settings.
+///
+/// # Errors
+///
+/// This function fails for data that isn't valid GIF style LZW of code size 8.
+///
+/// # Examples
+/// ```
+/// let compressed = salzweg::compress(&[0, 0, 1, 3]);
+///
+/// assert_eq!(salzweg::decompress(&compressed), Ok(vec![0, 0, 1, 3]));
+/// assert!(salzweg::decompress(&[0xff, 0xff]).is_err());
+/// ```
+pub fn decompress(data: &[u8]) -> Result<alloc::vec::Vec<u8>, decoder::DecodingError> {
+    decoder::GifStyleDecoder::decode_to_vec(data, 8)
+}
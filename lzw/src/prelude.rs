@@ -0,0 +1,22 @@
+//! The types most uses of the crate need, to import all at once.
+//!
+//! # Examples
+//! ```
+//! use salzweg::prelude::*;
+//!
+//! fn main() -> Result<(), DecodingError> {
+//!     let data = [0, 0, 1, 3];
+//!
+//!     let compressed = GifStyleEncoder::encode_to_vec(&data[..], 2).expect("Compression failed");
+//!     let decompressed = GifStyleDecoder::decode_to_vec(&compressed[..], 2)?;
+//!
+//!     assert_eq!(decompressed, data);
+//!     Ok(())
+//! }
+//! ```
+
+pub use crate::{
+    decoder::{DecodingError, FixedDecoder, GifStyleDecoder, TiffStyleDecoder},
+    encoder::{EncodingError, FixedEncoder, GifStyleEncoder, TiffStyleEncoder},
+    CodeSizeStrategy, Endianness,
+};
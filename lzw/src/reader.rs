@@ -0,0 +1,742 @@
+//! Contains pull based implementations, compressing or decompressing data as it is being read.
+
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+
+use crate::{
+    decoder::{
+        CodeWidth, DecodedBytes, DecodingError, DecodingTables, GifStyleDecoder, TiffStyleDecoder,
+        WordDecoder,
+    },
+    encoder::{EncodingError, FixedState},
+    io::{BitReader, BitWriter, LittleEndianReader, LittleEndianWriter},
+    CodeSizeStrategy, DictionaryPolicy,
+};
+
+/// LZW encoder writing fixed size codes, implementing [std::io::Read].
+///
+/// Wraps a source of uncompressed data: reading from the [FixedEncoder] pulls data from the
+/// source and hands back the compressed bytes, packed in little endian order.
+///
+/// As with [crate::encoder::FixedEncoder], there is no clear or end of information codes:
+/// As soon as the dictionary is full, we stop increasing its size.
+///
+/// # Examples
+/// ```
+/// use salzweg::{encoder::EncodingError, reader::FixedEncoder};
+/// use std::io::Read;
+///
+/// fn main() -> Result<(), EncodingError> {
+///     let data = [0, 0, 1, 3];
+///     let mut output = vec![];
+///
+///     FixedEncoder::new(&data[..], 12)?.read_to_end(&mut output)?;
+///
+///     assert_eq!(output, [0x0, 0x0, 0x0, 0x1, 0x30, 0x0]);
+///     Ok(())
+/// }
+/// ```
+pub struct FixedEncoder<R>
+where
+    R: Read,
+{
+    inner: R,
+    state: FixedState,
+    bit_writer: LittleEndianWriter<Vec<u8>>,
+    position: usize,
+    finished: bool,
+}
+
+impl<R> FixedEncoder<R>
+where
+    R: Read,
+{
+    /// Create a pull based encoder.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The source data to be compressed.
+    /// * `bits` - Between 9 and 16, the width of every written code, as with
+    ///   [crate::encoder::FixedEncoder::encode_with_bits]. This is not a GIF style code size:
+    ///   the dictionary holds up to 2.pow(bits) entries, and a width of 12 produces the same
+    ///   output as [crate::encoder::FixedEncoder::encode].
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [EncodingError::MaxCodeSize] if the width is not between
+    /// 9 and 16.
+    pub fn new(inner: R, bits: u8) -> Result<Self, EncodingError> {
+        if !(9..=16).contains(&bits) {
+            return Err(EncodingError::MaxCodeSize(bits));
+        }
+
+        Ok(Self {
+            inner,
+            state: FixedState::new(bits),
+            bit_writer: LittleEndianWriter::buffered(vec![]),
+            position: 0,
+            finished: false,
+        })
+    }
+
+    /// Unwraps this [FixedEncoder], returning the underlying reader.
+    ///
+    /// Data already pulled from the reader but not yet handed back compressed is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Pull data from the inner reader until some compressed bytes are available,
+    /// or the end of the stream is reached.
+    fn fill_output(&mut self) -> Result<(), std::io::Error> {
+        let mut chunk = [0; 1024];
+
+        while self.bit_writer.get_mut().is_empty() && !self.finished {
+            let read = match self.inner.read(&mut chunk) {
+                Ok(read) => read,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+
+            if read == 0 {
+                self.state.finish(&mut self.bit_writer)?;
+                self.bit_writer.fill()?;
+                self.finished = true;
+            } else {
+                for &k in &chunk[..read] {
+                    self.state.push(k, &mut self.bit_writer)?;
+                }
+                // Hand out the bytes of each chunk, rather than waiting for more.
+                self.bit_writer.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<R> Read for FixedEncoder<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, std::io::Error> {
+        self.fill_output()?;
+
+        let output = self.bit_writer.get_mut();
+        let available = &output[self.position..];
+        let amount = available.len().min(buf.len());
+        buf[..amount].copy_from_slice(&available[..amount]);
+
+        self.position += amount;
+        if self.position == output.len() {
+            output.clear();
+            self.position = 0;
+        }
+
+        Ok(amount)
+    }
+}
+
+/// LZW decoder reading fixed size codes, implementing [std::io::Read].
+///
+/// Mirrors [FixedEncoder]: wraps a source of compressed data, packed in little endian order,
+/// and decodes it on demand as the [FixedDecoderReader] is read.
+///
+/// Words are decoded one at a time, so reading with a small buffer never decodes more
+/// than needed: the remainder of a long word is kept for the next read.
+///
+/// # Examples
+/// ```
+/// use salzweg::{decoder::DecodingError, reader::FixedDecoderReader};
+/// use std::io::Read;
+///
+/// fn main() -> Result<(), DecodingError> {
+///     let data = [0x0, 0x0, 0x0, 0x1, 0x30, 0x0];
+///     let mut output = vec![];
+///
+///     FixedDecoderReader::new(&data[..], 12)?.read_to_end(&mut output)?;
+///
+///     assert_eq!(output, [0, 0, 1, 3]);
+///     Ok(())
+/// }
+/// ```
+pub struct FixedDecoderReader<R>
+where
+    R: Read,
+{
+    bit_reader: LittleEndianReader<R>,
+    bits: u8,
+    // Same decoding table as the one of [crate::decoder::FixedDecoder], but on the heap
+    // as it can get quite big for wider codes.
+    prefix: Vec<u16>,
+    suffix: Vec<u8>,
+    length: Vec<usize>,
+    decoding_stack: Vec<u8>,
+    words: WordDecoder,
+    // Every code has the same width: only there for the decoding core, it never grows.
+    code_width: CodeWidth,
+    // How much of the word currently in the decoding stack was already read.
+    position: usize,
+    // Where the compressed data starts in the inner reader, to restart from.
+    start: u64,
+}
+
+impl<R> FixedDecoderReader<R>
+where
+    R: Read,
+{
+    /// Create a pull based decoder.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The source data to be decoded.
+    /// * `bits` - Between 9 and 16, the width of every read code, as with
+    ///   [crate::decoder::FixedDecoder::decode_with_bits]. This is not a GIF style code size:
+    ///   it should match the width used by the [FixedEncoder] that compressed the data.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [DecodingError::MaxCodeSize] if the width is not between
+    /// 9 and 16.
+    pub fn new(inner: R, bits: u8) -> Result<Self, DecodingError> {
+        if !(9..=16).contains(&bits) {
+            return Err(DecodingError::MaxCodeSize(bits));
+        }
+
+        let table_size = 1 << bits;
+        let mut prefix = vec![0; table_size];
+        let mut suffix = vec![0; table_size];
+        let mut length = vec![0; table_size];
+        // The longest word is made of the 256 initial entries, plus all the other entries
+        // built one on top of the other.
+        let decoding_stack = vec![0; table_size - 255];
+        for code in 0..256 {
+            prefix[code] = 0;
+            suffix[code] = code as u8;
+            length[code] = 1;
+        }
+
+        Ok(Self {
+            bit_reader: LittleEndianReader::new(inner),
+            bits,
+            prefix,
+            suffix,
+            length,
+            decoding_stack,
+            // As the dictionary stops growing once full, there is no need for a clear code.
+            words: WordDecoder::new(256, table_size, DictionaryPolicy::Freeze, 256),
+            code_width: CodeWidth::new(bits, bits, CodeSizeStrategy::Default),
+            position: 0,
+            start: 0,
+        })
+    }
+
+    /// Unwraps this [FixedDecoderReader], returning the underlying reader.
+    ///
+    /// Bits already pulled from the reader but not yet decoded are lost.
+    pub fn into_inner(self) -> R {
+        self.bit_reader.into_inner()
+    }
+
+    /// Decode the next word in the decoding stack. Returns false once the data is exhausted.
+    fn decode_next_word(&mut self) -> Result<bool, DecodingError> {
+        let mut buf = [0; 1];
+        if self.bit_reader.read(self.bits, &mut buf)? == 0 {
+            return Ok(false);
+        }
+        let code = buf[0];
+
+        self.position = 0;
+
+        let mut tables = DecodingTables {
+            prefix: &mut self.prefix,
+            suffix: &mut self.suffix,
+            length: &mut self.length,
+            decoding_stack: &mut self.decoding_stack,
+        };
+        self.words.decode(code, &mut tables)?;
+        self.words
+            .add_entry(code, &mut tables, &mut self.code_width)?;
+
+        Ok(true)
+    }
+}
+
+impl<R> FixedDecoderReader<R>
+where
+    R: Read + Seek,
+{
+    /// Create a pull based decoder that can [restart](FixedDecoderReader::restart), recording
+    /// the current position of `inner` as the start of the compressed data.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The source data to be decoded, positioned where the compressed data starts.
+    /// * `bits` - Between 9 and 16, the width of every read code, as with
+    ///   [crate::decoder::FixedDecoder::decode_with_bits]. This is not a GIF style code size:
+    ///   it should match the width used by the [FixedEncoder] that compressed the data.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the position of `inner` can't be queried, or with
+    /// [DecodingError::MaxCodeSize] if the width is not between 9 and 16.
+    pub fn new_seekable(inner: R, bits: u8) -> Result<Self, DecodingError> {
+        let mut inner = inner;
+        let start = inner.stream_position()?;
+
+        let mut decoder = Self::new(inner, bits)?;
+        decoder.start = start;
+        Ok(decoder)
+    }
+
+    /// Seek the inner reader back to the start of the compressed data, and decode it again
+    /// from there, as a new decoder would.
+    ///
+    /// The start is the position recorded by [FixedDecoderReader::new_seekable], or the
+    /// beginning of the inner reader for a decoder created with [FixedDecoderReader::new].
+    /// The decoding tables are kept, so restarting doesn't allocate.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the inner reader can't seek.
+    ///
+    /// # Examples
+    /// ```
+    /// use salzweg::{decoder::DecodingError, reader::FixedDecoderReader};
+    /// use std::io::{Cursor, Read};
+    ///
+    /// fn main() -> Result<(), DecodingError> {
+    ///     let data = [0x0, 0x0, 0x0, 0x1, 0x30, 0x0];
+    ///     let mut decoder = FixedDecoderReader::new_seekable(Cursor::new(&data[..]), 12)?;
+    ///
+    ///     let mut first = vec![];
+    ///     decoder.read_to_end(&mut first)?;
+    ///     decoder.restart()?;
+    ///     let mut second = vec![];
+    ///     decoder.read_to_end(&mut second)?;
+    ///
+    ///     assert_eq!(first, [0, 0, 1, 3]);
+    ///     assert_eq!(second, first);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn restart(&mut self) -> Result<(), std::io::Error> {
+        self.bit_reader
+            .get_mut()
+            .seek(SeekFrom::Start(self.start))?;
+        self.bit_reader.clear();
+
+        // Only the entries past the single digits get rewritten, the others never change.
+        self.words.clear(256);
+        self.words.word_length = 0;
+        self.position = 0;
+
+        Ok(())
+    }
+}
+
+impl<R> Read for FixedDecoderReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, std::io::Error> {
+        let mut done = 0;
+
+        while done < buf.len() {
+            if self.position == self.words.word_length {
+                match self.decode_next_word() {
+                    Ok(true) => {}
+                    Ok(false) => break,
+                    Err(DecodingError::Io(error)) => return Err(error),
+                    Err(error) => {
+                        return Err(std::io::Error::new(ErrorKind::InvalidData, error));
+                    }
+                }
+            }
+
+            let available = &self.decoding_stack[self.position..self.words.word_length];
+            let amount = available.len().min(buf.len() - done);
+            buf[done..done + amount].copy_from_slice(&available[..amount]);
+
+            self.position += amount;
+            done += amount;
+        }
+
+        Ok(done)
+    }
+}
+
+/// LZW decoder for variable code size data, GIF or TIFF style, implementing [std::io::Read].
+///
+/// Wraps a source of compressed data and decodes it on demand as the [DecoderReader] is read.
+/// Codes are only decoded while the buffer passed to [read](Read::read) has room left:
+/// with a small buffer, the remainder of a long word is kept for the next read.
+///
+/// Decoding errors other than I/O ones are reported as [ErrorKind::InvalidData], wrapping
+/// the [DecodingError]. Bytes decoded before an error are read first.
+///
+/// The source is read ahead, past the end of information code: see
+/// [reading ahead](crate::decoder#reading-ahead).
+///
+/// # Examples
+/// ```
+/// use salzweg::reader::DecoderReader;
+/// use std::io::Read;
+///
+/// fn main() -> Result<(), std::io::Error> {
+///     let data = [0x04, 0x32, 0x05];
+///     let mut output = vec![];
+///
+///     DecoderReader::gif(&data[..], 2).read_to_end(&mut output)?;
+///
+///     assert_eq!(output, [0, 0, 1, 3]);
+///     Ok(())
+/// }
+/// ```
+pub struct DecoderReader<R>
+where
+    R: Read,
+{
+    bytes: DecodedBytes<R>,
+}
+
+impl<R> DecoderReader<R>
+where
+    R: Read,
+{
+    /// Create a pull based decoder for GIF style data, as decoded by [GifStyleDecoder].
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The source data to be decoded.
+    /// * `code_size` - Between 2 and 8, the initial code size to use. An invalid code size is
+    ///   reported by the first read.
+    pub fn gif(inner: R, code_size: u8) -> Self {
+        Self {
+            bytes: GifStyleDecoder::decode_iter(inner, code_size),
+        }
+    }
+
+    /// Create a pull based decoder for TIFF style data, as decoded by [TiffStyleDecoder].
+    ///
+    /// As with [TiffStyleDecoder], the end of information code is optional.
+    pub fn tiff(inner: R) -> Self {
+        Self {
+            bytes: TiffStyleDecoder::decode_iter(inner),
+        }
+    }
+}
+
+impl<R> Read for DecoderReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, std::io::Error> {
+        match self.bytes.read_into(buf) {
+            Ok(amount) => Ok(amount),
+            Err(DecodingError::Io(error)) => Err(error),
+            Err(error) => Err(std::io::Error::new(ErrorKind::InvalidData, error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decoder, encoder, Endianness};
+
+    #[test]
+    fn compare_fixed_encoders() -> Result<(), std::io::Error> {
+        let lorem_ipsum = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        let colors = [
+            1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 2, 2, 2, 2,
+            2, 1, 1, 1, 0, 0, 0, 0, 2, 2, 2,
+        ];
+
+        for data in [&lorem_ipsum[..], &colors[..], &[]] {
+            let expected =
+                encoder::FixedEncoder::encode_to_vec(data, Endianness::LittleEndian).unwrap();
+
+            let mut output = vec![];
+            FixedEncoder::new(data, 12)
+                .unwrap()
+                .read_to_end(&mut output)?;
+
+            assert_eq!(output, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_with_tiny_buffer() -> Result<(), std::io::Error> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        let expected =
+            encoder::FixedEncoder::encode_to_vec(&data[..], Endianness::LittleEndian).unwrap();
+
+        let mut encoder = FixedEncoder::new(&data[..], 12).unwrap();
+        let mut output = vec![];
+        let mut buf = [0; 3];
+        loop {
+            let read = encoder.read(&mut buf)?;
+            if read == 0 {
+                break;
+            }
+            output.extend_from_slice(&buf[..read]);
+        }
+
+        assert_eq!(output, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn encode_16_bit_codes() -> Result<(), std::io::Error> {
+        let data = [0, 0, 1, 3];
+
+        let mut output = vec![];
+        FixedEncoder::new(&data[..], 16)
+            .unwrap()
+            .read_to_end(&mut output)?;
+
+        assert_eq!(output, [0x0, 0x0, 0x0, 0x0, 0x1, 0x0, 0x3, 0x0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn encoder_bits_out_of_range() {
+        for bits in [8, 17] {
+            let result = FixedEncoder::new(&[0][..], bits);
+            assert_eq!(result.err(), Some(EncodingError::MaxCodeSize(bits)));
+        }
+    }
+
+    #[test]
+    fn into_inner_returns_reader() -> Result<(), std::io::Error> {
+        let data = [0, 0, 1, 3];
+        let mut encoder = FixedEncoder::new(&data[..], 12).unwrap();
+        let mut output = vec![];
+        encoder.read_to_end(&mut output)?;
+
+        let inner = encoder.into_inner();
+        assert!(inner.is_empty());
+
+        assert_eq!(
+            decoder::FixedDecoder::decode_to_vec(&output[..], Endianness::LittleEndian).unwrap(),
+            data
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn compare_fixed_decoders() -> Result<(), std::io::Error> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        let compressed =
+            encoder::FixedEncoder::encode_to_vec(&data[..], Endianness::LittleEndian).unwrap();
+        let expected =
+            decoder::FixedDecoder::decode_to_vec(&compressed[..], Endianness::LittleEndian)
+                .unwrap();
+
+        let mut output = vec![];
+        FixedDecoderReader::new(&compressed[..], 12)
+            .unwrap()
+            .read_to_end(&mut output)?;
+
+        assert_eq!(output, expected);
+        assert_eq!(output, data);
+
+        Ok(())
+    }
+
+    #[test]
+    fn decoder_bits_out_of_range() {
+        for bits in [8, 17] {
+            let result = FixedDecoderReader::new(&[0][..], bits);
+            assert_eq!(result.err(), Some(DecodingError::MaxCodeSize(bits)));
+
+            let result = FixedDecoderReader::new_seekable(std::io::Cursor::new([0]), bits);
+            assert_eq!(result.err(), Some(DecodingError::MaxCodeSize(bits)));
+        }
+    }
+
+    #[test]
+    fn decode_with_tiny_buffer() -> Result<(), std::io::Error> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        let compressed =
+            encoder::FixedEncoder::encode_to_vec(&data[..], Endianness::LittleEndian).unwrap();
+
+        let mut decoder = FixedDecoderReader::new(&compressed[..], 12).unwrap();
+        let mut output = vec![];
+        let mut buf = [0; 2];
+        loop {
+            let read = decoder.read(&mut buf)?;
+            if read == 0 {
+                break;
+            }
+            output.extend_from_slice(&buf[..read]);
+        }
+
+        assert_eq!(output, data);
+
+        Ok(())
+    }
+
+    #[test]
+    fn copy_between_fixed_encoder_and_decoder() -> Result<(), std::io::Error> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+
+        for bits in [9, 12, 16] {
+            let encoder = FixedEncoder::new(&data[..], bits).unwrap();
+            let mut output = vec![];
+            std::io::copy(
+                &mut FixedDecoderReader::new(encoder, bits).unwrap(),
+                &mut output,
+            )?;
+
+            assert_eq!(output, data, "Width {bits}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn restart_decodes_again_from_the_start() -> Result<(), std::io::Error> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        let compressed =
+            encoder::FixedEncoder::encode_to_vec(&data[..], Endianness::LittleEndian).unwrap();
+
+        // The compressed data doesn't start at the beginning of the stream.
+        let mut stream = vec![0xff; 7];
+        stream.extend_from_slice(&compressed);
+        let mut cursor = std::io::Cursor::new(stream);
+        cursor.seek(SeekFrom::Start(7))?;
+
+        let mut decoder = FixedDecoderReader::new_seekable(cursor, 12).unwrap();
+
+        // Restart both after a partial read and after reading everything.
+        let mut partial = [0; 100];
+        decoder.read_exact(&mut partial)?;
+        decoder.restart()?;
+
+        let mut first = vec![];
+        decoder.read_to_end(&mut first)?;
+        decoder.restart()?;
+        let mut second = vec![];
+        decoder.read_to_end(&mut second)?;
+
+        assert_eq!(&partial[..], &data[..100]);
+        assert_eq!(first, data);
+        assert_eq!(second, first);
+
+        Ok(())
+    }
+
+    #[test]
+    fn decode_unexpected_code() {
+        // Code 300 while the dictionary only has 256 entries.
+        let data = [0x1, 0xc0, 0x12];
+        let mut output = vec![];
+
+        let error = FixedDecoderReader::new(&data[..], 12)
+            .unwrap()
+            .read_to_end(&mut output)
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn fixed_decoders_agree_on_a_first_code_not_a_byte() {
+        // A single 12 bit code of 300, little endian.
+        let data = [0x2c, 0x01];
+        let expected =
+            decoder::FixedDecoder::decode_to_vec(&data[..], Endianness::LittleEndian).unwrap_err();
+        assert_eq!(expected, DecodingError::InvalidCodeSequence(300));
+
+        let mut output = vec![];
+        let error = FixedDecoderReader::new(&data[..], 12)
+            .unwrap()
+            .read_to_end(&mut output)
+            .unwrap_err();
+
+        assert!(output.is_empty());
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        let error = error
+            .into_inner()
+            .unwrap()
+            .downcast::<DecodingError>()
+            .unwrap();
+        assert_eq!(*error, expected);
+    }
+
+    fn read_in_chunks(
+        reader: &mut impl Read,
+        chunk_size: usize,
+    ) -> Result<Vec<u8>, std::io::Error> {
+        let mut output = vec![];
+        let mut buf = vec![0; chunk_size];
+        loop {
+            let read = reader.read(&mut buf)?;
+            if read == 0 {
+                return Ok(output);
+            }
+            output.extend_from_slice(&buf[..read]);
+        }
+    }
+
+    #[test]
+    fn decode_variable_with_tiny_buffer() -> Result<(), std::io::Error> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        let gif = encoder::GifStyleEncoder::encode_to_vec(&data[..], 7).unwrap();
+        let tiff = encoder::TiffStyleEncoder::encode_to_vec(&data[..]).unwrap();
+
+        for chunk_size in [1, 2] {
+            let output = read_in_chunks(&mut DecoderReader::gif(&gif[..], 7), chunk_size)?;
+            assert_eq!(output, data, "GIF, buffer of {chunk_size}");
+
+            let output = read_in_chunks(&mut DecoderReader::tiff(&tiff[..]), chunk_size)?;
+            assert_eq!(output, data, "TIFF, buffer of {chunk_size}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn decode_long_words_across_reads() -> Result<(), std::io::Error> {
+        // A single repeated byte makes every word one byte longer than the previous one.
+        let data = vec![42; 5000];
+        let compressed = encoder::GifStyleEncoder::encode_to_vec(&data[..], 8).unwrap();
+
+        let output = read_in_chunks(&mut DecoderReader::gif(&compressed[..], 8), 3)?;
+
+        assert_eq!(output, data);
+
+        Ok(())
+    }
+
+    #[test]
+    fn decode_variable_errors() {
+        // Code 300 while the next free entry is 259.
+        let mut data = vec![];
+        crate::io::pack_codes(
+            &[256, 1, 2, 300, 257],
+            &[9; 5],
+            &mut data,
+            Endianness::LittleEndian,
+        )
+        .unwrap();
+
+        // The bytes decoded before the error are read first.
+        let mut decoder = DecoderReader::gif(&data[..], 8);
+        let mut buf = [0; 10];
+        assert_eq!(decoder.read(&mut buf).unwrap(), 2);
+        assert_eq!(buf[..2], [1, 2]);
+        let error = decoder.read(&mut buf).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(decoder.read(&mut buf).unwrap(), 0);
+
+        let error = DecoderReader::gif(&data[..], 9).read(&mut buf).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+}
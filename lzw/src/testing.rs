@@ -0,0 +1,127 @@
+//! Helpers for testing code built on top of the crate, behind the `testing` feature.
+//!
+//! Fuzzers and property tests mostly need the same check: compressing then decompressing
+//! some data should give the data back.
+
+use crate::{decoder::VariableDecoder, encoder::VariableEncoder, CodeSizeStrategy, Endianness};
+
+/// GIF and TIFF both cap the code size at 12 bits.
+const MAX_CODE_SIZE: u8 = 12;
+
+/// Whether `data` survives being compressed then decompressed with variable code size LZW.
+///
+/// Encoding failures count as a failed round trip too, for instance with values of `data`
+/// too big for the code size.
+///
+/// # Arguments
+///
+/// * `data` - The data to compress.
+/// * `code_size` - Between 1 and 8, the initial code size to use.
+/// * `endianness` - Bit ordering of the compressed data.
+/// * `code_size_strategy` - The strategy to use for increasing the code size.
+///
+/// # Examples
+/// ```
+/// use salzweg::{testing, CodeSizeStrategy, Endianness};
+///
+/// let data = [0, 0, 1, 3];
+///
+/// assert!(testing::roundtrip(&data, 2, Endianness::LittleEndian, CodeSizeStrategy::Default));
+/// // 3 doesn't fit in a code size of 1.
+/// assert!(!testing::roundtrip(&data, 1, Endianness::LittleEndian, CodeSizeStrategy::Default));
+/// ```
+pub fn roundtrip(
+    data: &[u8],
+    code_size: u8,
+    endianness: Endianness,
+    code_size_strategy: CodeSizeStrategy,
+) -> bool {
+    let compressed = match VariableEncoder::encode_to_vec(
+        data,
+        code_size,
+        MAX_CODE_SIZE,
+        endianness,
+        code_size_strategy,
+    ) {
+        Ok(compressed) => compressed,
+        Err(_) => return false,
+    };
+
+    match VariableDecoder::decode_to_vec(
+        &compressed[..],
+        code_size,
+        MAX_CODE_SIZE,
+        endianness,
+        code_size_strategy,
+    ) {
+        Ok(decompressed) => decompressed == data,
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    /// A tiny linear congruential generator: reproducible random inputs, without a dependency.
+    struct Random(u32);
+
+    impl Random {
+        fn next(&mut self) -> u32 {
+            self.0 = self.0.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            self.0 >> 16
+        }
+
+        fn below(&mut self, bound: u32) -> u32 {
+            self.next() % bound
+        }
+    }
+
+    /// Random data of values below 2.pow(code_size): either noise, runs of a few values, or
+    /// a single value repeated, which builds the longest words the dictionary can hold.
+    fn random_data(random: &mut Random, code_size: u8) -> Vec<u8> {
+        let length = random.below(6_000) as usize;
+        let symbols = 1 << code_size;
+        match random.below(3) {
+            0 => (0..length).map(|_| random.below(symbols) as u8).collect(),
+            1 => {
+                let mut data = Vec::with_capacity(length);
+                while data.len() < length {
+                    let value = random.below(symbols) as u8;
+                    let run = 1 + random.below(64) as usize;
+                    data.resize(data.len() + run, value);
+                }
+                data.truncate(length);
+                data
+            }
+            _ => {
+                let value = random.below(symbols) as u8;
+                alloc::vec![value; length]
+            }
+        }
+    }
+
+    #[test]
+    fn random_round_trips() {
+        let mut random = Random(1_234);
+
+        for code_size in 1..=8 {
+            for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+                for code_size_strategy in [CodeSizeStrategy::Default, CodeSizeStrategy::Tiff] {
+                    for _ in 0..16 {
+                        let seed = random.next();
+                        let data = random_data(&mut Random(seed), code_size);
+
+                        assert!(
+                            roundtrip(&data, code_size, endianness, code_size_strategy),
+                            "code size {code_size}, {endianness:?}, {code_size_strategy:?}, \
+                             seed {seed}",
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,418 @@
+//! Repacks variable code size LZW from one flavor to another, without decoding it.
+//!
+//! GIF and TIFF data of the same code size hold the same codes: only the bit ordering and the
+//! point where the code size increases differ. Moving a code stream from one to the other
+//! then only takes reading each code with the widths of the source, and writing it back with
+//! the widths of the destination.
+//!
+//! Fixed code size data is the exception: without clear codes, its dictionary doesn't match
+//! the GIF one, so [fixed_to_gif] decodes it and encodes it again, in a single pass.
+//!
+//! As with the decoders, the source is read ahead, past the end of information code: see
+//! [reading ahead](crate::decoder#reading-ahead).
+
+use crate::{
+    decoder::{check_code_sizes, CodeWidth, DecodedBytes, DecodingError, FixedDecoder},
+    encoder::{EncodingError, GifStyleEncoder},
+    io::{
+        self, BigEndianReader, BigEndianWriter, BitReader, BitWriter, LittleEndianReader,
+        LittleEndianWriter, Read, Write,
+    },
+    CodeSizeStrategy, Endianness,
+};
+
+/// GIF and TIFF both cap the code size at 12 bits.
+const MAX_CODE_SIZE: u8 = 12;
+/// TIFF always uses a code size of 8, the only GIF code size both can share.
+const TIFF_CODE_SIZE: u8 = 8;
+
+/// Repack the codes read from `bit_reader` into `bit_writer`, passing them through unchanged.
+///
+/// The size of the codes is tracked independently on each side: the read size follows the
+/// `from` strategy, the write size the `to` strategy. The last byte written is padded with
+/// zeros, as the encoders do.
+///
+/// # Arguments
+///
+/// * `bit_reader` - The source of the codes.
+/// * `bit_writer` - Where the codes are written.
+/// * `code_size` - Between 1 and 8, the initial code size, shared by both sides.
+/// * `max_code_size` - At most 16, and at least the initial read size: the maximum size
+///   of the codes, shared by both sides. GIF and TIFF use a maximum code size of 12.
+/// * `from` - The strategy the codes were written with.
+/// * `to` - The strategy to write the codes with.
+///
+/// # Errors
+///
+/// This function can fail on an [io::Error](crate::io::Error), for unsupported code sizes, or
+/// for codes the dictionary couldn't hold yet.
+///
+/// # Examples
+/// ```
+/// use salzweg::{
+///     decoder::DecodingError,
+///     encoder::{GifStyleEncoder, TiffStyleEncoder},
+///     io::{BigEndianWriter, LittleEndianReader},
+///     transcoder, CodeSizeStrategy,
+/// };
+///
+/// fn main() -> Result<(), DecodingError> {
+///     let data = [0, 0, 1, 3];
+///     let gif = GifStyleEncoder::encode_to_vec(&data[..], 8).unwrap();
+///
+///     let mut tiff = vec![];
+///     transcoder::transcode(
+///         LittleEndianReader::new(&gif[..]),
+///         BigEndianWriter::new(&mut tiff),
+///         8,
+///         12,
+///         CodeSizeStrategy::Default,
+///         CodeSizeStrategy::Tiff,
+///     )?;
+///
+///     assert_eq!(tiff, TiffStyleEncoder::encode_to_vec(&data[..]).unwrap());
+///     Ok(())
+/// }
+/// ```
+pub fn transcode<B: BitReader, W: BitWriter>(
+    bit_reader: B,
+    bit_writer: W,
+    code_size: u8,
+    max_code_size: u8,
+    from: CodeSizeStrategy,
+    to: CodeSizeStrategy,
+) -> Result<(), DecodingError> {
+    check_code_sizes(code_size, max_code_size)?;
+
+    let mut bit_reader = bit_reader;
+    let mut bit_writer = bit_writer;
+
+    let clear_code = 1 << code_size;
+    let end_of_information = clear_code + 1;
+    let table_size = 1 << max_code_size;
+
+    let mut read_width = CodeWidth::new(code_size + 1, max_code_size, from);
+    let mut write_width = CodeWidth::new(code_size + 1, max_code_size, to);
+    let mut next_index = clear_code as usize + 2;
+    // The first code after a clear code doesn't add any word to the dictionary.
+    let mut first_code = true;
+
+    loop {
+        let code = bit_reader.read_one(read_width.get())?;
+        bit_writer.write(code, write_width.get())?;
+
+        if code == clear_code {
+            read_width.reset();
+            write_width.reset();
+            next_index = clear_code as usize + 2;
+            first_code = true;
+            continue;
+        } else if code == end_of_information {
+            break;
+        } else if first_code {
+            // Only the single symbols are known before the first word.
+            if code >= clear_code {
+                return Err(DecodingError::InvalidCodeSequence(code));
+            }
+            first_code = false;
+        } else if code as usize > next_index {
+            return Err(DecodingError::UnexpectedCode(code));
+        } else if next_index < table_size {
+            next_index += 1;
+        }
+        read_width.grow(next_index);
+        write_width.grow(next_index);
+    }
+
+    bit_writer.fill()?;
+    bit_writer.flush()?;
+
+    Ok(())
+}
+
+/// Repack GIF data of code size 8 as TIFF data.
+///
+/// # Arguments
+///
+/// * `data` - The GIF compressed data, of code size 8.
+/// * `into` - Where the TIFF compressed data will be written.
+///
+/// # Errors
+///
+/// This function can fail on an [io::Error](crate::io::Error), or for codes the dictionary
+/// couldn't hold yet.
+///
+/// # Examples
+/// ```
+/// use salzweg::{
+///     decoder::{DecodingError, TiffStyleDecoder},
+///     encoder::GifStyleEncoder,
+///     transcoder,
+/// };
+///
+/// fn main() -> Result<(), DecodingError> {
+///     let data = [0, 0, 1, 3];
+///     let gif = GifStyleEncoder::encode_to_vec(&data[..], 8).unwrap();
+///
+///     let mut tiff = vec![];
+///     transcoder::gif_to_tiff(&gif[..], &mut tiff)?;
+///
+///     assert_eq!(TiffStyleDecoder::decode_to_vec(&tiff[..])?, data);
+///     Ok(())
+/// }
+/// ```
+pub fn gif_to_tiff<R: Read, W: Write>(data: R, into: W) -> Result<(), DecodingError> {
+    transcode(
+        LittleEndianReader::new(data),
+        BigEndianWriter::buffered(into),
+        TIFF_CODE_SIZE,
+        MAX_CODE_SIZE,
+        CodeSizeStrategy::Default,
+        CodeSizeStrategy::Tiff,
+    )
+}
+
+/// Repack TIFF data as GIF data, of code size 8.
+///
+/// # Arguments
+///
+/// * `data` - The TIFF compressed data.
+/// * `into` - Where the GIF compressed data will be written.
+///
+/// # Errors
+///
+/// This function can fail on an [io::Error](crate::io::Error), or for codes the dictionary
+/// couldn't hold yet.
+///
+/// # Examples
+/// ```
+/// use salzweg::{
+///     decoder::{DecodingError, GifStyleDecoder},
+///     transcoder,
+/// };
+///
+/// fn main() -> Result<(), DecodingError> {
+///     let tiff = [0x80, 0x0, 0x0, 0x0, 0x10, 0x1c, 0x4];
+///
+///     let mut gif = vec![];
+///     transcoder::tiff_to_gif(&tiff[..], &mut gif)?;
+///
+///     assert_eq!(GifStyleDecoder::decode_to_vec(&gif[..], 8)?, [0, 0, 1, 3]);
+///     Ok(())
+/// }
+/// ```
+pub fn tiff_to_gif<R: Read, W: Write>(data: R, into: W) -> Result<(), DecodingError> {
+    transcode(
+        BigEndianReader::new(data),
+        LittleEndianWriter::buffered(into),
+        TIFF_CODE_SIZE,
+        MAX_CODE_SIZE,
+        CodeSizeStrategy::Tiff,
+        CodeSizeStrategy::Default,
+    )
+}
+
+/// Convert fixed code size data, as written by [FixedEncoder](crate::encoder::FixedEncoder),
+/// to GIF data.
+///
+/// The codes can't be repacked as they are: the data is decoded, and the decoded bytes are
+/// fed to the GIF encoder as they come, without holding the whole decoded data in memory.
+///
+/// # Arguments
+///
+/// * `data` - The fixed code size compressed data.
+/// * `endianness` - The bit ordering `data` was packed with.
+/// * `code_size` - Between 1 and 8, the initial code size of the GIF data. Every decoded byte
+///   must fit in it: 8 takes any data.
+/// * `into` - Where the GIF compressed data will be written.
+///
+/// # Errors
+///
+/// This function can fail on an [io::Error], for an unsupported code size, for invalid fixed
+/// code size data, or with [DecodingError::UnexpectedCode] for a decoded byte that doesn't
+/// fit in `code_size`.
+///
+/// # Examples
+/// ```
+/// use salzweg::{
+///     decoder::{DecodingError, GifStyleDecoder},
+///     encoder::FixedEncoder,
+///     transcoder, Endianness,
+/// };
+///
+/// fn main() -> Result<(), DecodingError> {
+///     let data = [0, 0, 1, 3];
+///     let fixed = FixedEncoder::encode_to_vec(&data[..], Endianness::LittleEndian).unwrap();
+///
+///     let mut gif = vec![];
+///     transcoder::fixed_to_gif(&fixed[..], Endianness::LittleEndian, 2, &mut gif)?;
+///
+///     assert_eq!(GifStyleDecoder::decode_to_vec(&gif[..], 2)?, data);
+///     Ok(())
+/// }
+/// ```
+pub fn fixed_to_gif<R: Read, W: Write>(
+    data: R,
+    endianness: Endianness,
+    code_size: u8,
+    into: W,
+) -> Result<(), DecodingError> {
+    check_code_sizes(code_size, MAX_CODE_SIZE)?;
+
+    let mut decoded = DecodedReader {
+        bytes: FixedDecoder::decode_iter(data, endianness),
+        error: None,
+    };
+
+    let result = GifStyleEncoder::encode(&mut decoded, into, code_size);
+    // The encoder only sees an I/O error: hand back the decoding error behind it.
+    if let Some(error) = decoded.error {
+        return Err(error);
+    }
+
+    result.map_err(|error| match error {
+        EncodingError::Io(error) => DecodingError::Io(error),
+        EncodingError::CodeSize(code_size) => DecodingError::CodeSize(code_size),
+        EncodingError::MaxCodeSize(max_code_size) => DecodingError::MaxCodeSize(max_code_size),
+        EncodingError::UnexpectedCode { code, .. } => DecodingError::UnexpectedCode(code),
+        EncodingError::Possibilities(possibilities) => DecodingError::Possibilities(possibilities),
+        EncodingError::InvalidDictionary => DecodingError::InvalidDictionary,
+    })
+}
+
+/// Reads the bytes of a decoder, keeping the decoding errors aside as the [Read] trait can
+/// only return [io::Error]s.
+struct DecodedReader<R>
+where
+    R: Read,
+{
+    bytes: DecodedBytes<R>,
+    error: Option<DecodingError>,
+}
+
+impl<R> Read for DecodedReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        match self.bytes.read_into(buf) {
+            Ok(amount) => Ok(amount),
+            Err(DecodingError::Io(error)) => Err(error),
+            Err(error) => {
+                self.error = Some(error);
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Invalid fixed code size data",
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::{
+        decoder::{GifStyleDecoder, TiffStyleDecoder},
+        encoder::{FixedEncoder, GifStyleEncoder, TiffStyleEncoder},
+    };
+
+    #[test]
+    fn gif_to_tiff_to_gif_round_trip() -> Result<(), DecodingError> {
+        // Long enough for the dictionary to fill up and be cleared a few times.
+        let data = include_bytes!("../../test-assets/sunflower.bmp");
+        let gif = GifStyleEncoder::encode_to_vec(&data[..], 8).unwrap();
+
+        let mut tiff = vec![];
+        gif_to_tiff(&gif[..], &mut tiff)?;
+        assert_eq!(TiffStyleDecoder::decode_to_vec(&tiff[..])?, data);
+
+        let mut back = vec![];
+        tiff_to_gif(&tiff[..], &mut back)?;
+        assert_eq!(back, gif);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tiff_to_gif_to_tiff_round_trip() -> Result<(), DecodingError> {
+        let data = include_bytes!("../../test-assets/lorem_ipsum.txt");
+        let tiff = TiffStyleEncoder::encode_to_vec(&data[..]).unwrap();
+
+        let mut gif = vec![];
+        tiff_to_gif(&tiff[..], &mut gif)?;
+        assert_eq!(GifStyleDecoder::decode_to_vec(&gif[..], 8)?, data);
+
+        let mut back = vec![];
+        gif_to_tiff(&gif[..], &mut back)?;
+        assert_eq!(back, tiff);
+
+        Ok(())
+    }
+
+    #[test]
+    fn transcode_code_past_next_index() {
+        // Clear code, then 0, then 300 while the next entry to be created is 258.
+        let mut gif = vec![];
+        let mut writer = LittleEndianWriter::new(&mut gif);
+        for code in [256, 0, 300, 257] {
+            writer.write(code, 9).unwrap();
+        }
+        writer.fill().unwrap();
+
+        let result = gif_to_tiff(&gif[..], vec![]);
+        assert_eq!(result.err(), Some(DecodingError::UnexpectedCode(300)));
+    }
+
+    #[test]
+    fn transcode_first_code_not_a_byte() {
+        // Clear code, then 258 while no word is known yet.
+        let mut gif = vec![];
+        let mut writer = LittleEndianWriter::new(&mut gif);
+        for code in [256, 258, 257] {
+            writer.write(code, 9).unwrap();
+        }
+        writer.fill().unwrap();
+
+        let result = gif_to_tiff(&gif[..], vec![]);
+        assert_eq!(result.err(), Some(DecodingError::InvalidCodeSequence(258)));
+    }
+
+    #[test]
+    fn fixed_to_gif_round_trip() -> Result<(), DecodingError> {
+        // Long enough for the fixed dictionary to freeze, and the GIF one to be cleared.
+        let data = include_bytes!("../../test-assets/sunflower.bmp");
+
+        for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let fixed = FixedEncoder::encode_to_vec(&data[..], endianness).unwrap();
+
+            let mut gif = vec![];
+            fixed_to_gif(&fixed[..], endianness, 8, &mut gif)?;
+
+            assert_eq!(gif, GifStyleEncoder::encode_to_vec(&data[..], 8).unwrap());
+            assert_eq!(GifStyleDecoder::decode_to_vec(&gif[..], 8)?, data);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_to_gif_errors() {
+        let data = [0, 1, 2, 5];
+        let fixed = FixedEncoder::encode_to_vec(&data[..], Endianness::LittleEndian).unwrap();
+
+        let result = fixed_to_gif(&fixed[..], Endianness::LittleEndian, 9, vec![]);
+        assert_eq!(result.err(), Some(DecodingError::CodeSize(9)));
+
+        // 5 doesn't fit in a code size of 2.
+        let result = fixed_to_gif(&fixed[..], Endianness::LittleEndian, 2, vec![]);
+        assert_eq!(result.err(), Some(DecodingError::UnexpectedCode(5)));
+
+        // Code 300 while the dictionary only has 256 entries.
+        let invalid = [0x1, 0xc0, 0x12];
+        let result = fixed_to_gif(&invalid[..], Endianness::LittleEndian, 8, vec![]);
+        assert_eq!(result.err(), Some(DecodingError::UnexpectedCode(300)));
+    }
+}
@@ -15,6 +15,7 @@ fn compress_text_salzweg() {
         LOREM_IPSUM.as_bytes(),
         &mut compressed,
         7,
+        12,
         salzweg::Endianness::LittleEndian,
         salzweg::CodeSizeStrategy::Default,
     )
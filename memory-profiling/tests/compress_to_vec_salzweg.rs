@@ -0,0 +1,29 @@
+#[global_allocator]
+static ALLOC: dhat::Alloc = dhat::Alloc;
+
+const LOREM_IPSUM: &str = include_str!("../../test-assets/lorem_ipsum.txt");
+
+#[test]
+fn compress_to_vec_salzweg() {
+    let _profiler = dhat::Profiler::builder().testing().build();
+
+    let start_stats = dhat::HeapStats::get();
+
+    let compressed = salzweg::encoder::VariableEncoder::encode_to_vec(
+        LOREM_IPSUM.as_bytes(),
+        7,
+        12,
+        salzweg::Endianness::LittleEndian,
+        salzweg::CodeSizeStrategy::Default,
+    )
+    .unwrap();
+
+    let stats = dhat::HeapStats::get();
+
+    // The output is reserved from the length of the input, and never needs to grow.
+    dhat::assert!(compressed.len() <= LOREM_IPSUM.len());
+    dhat::assert_eq!(compressed.capacity(), LOREM_IPSUM.len());
+
+    println!("{start_stats:?}");
+    println!("{stats:?}");
+}
@@ -17,6 +17,7 @@ fn decompress_text_salzweg() {
         LOREM_IPSUM_ENCODED,
         &mut decompressed,
         7,
+        12,
         salzweg::Endianness::LittleEndian,
         CodeSizeStrategy::Default,
     )
@@ -0,0 +1,30 @@
+use salzweg::CodeSizeStrategy;
+
+#[global_allocator]
+static ALLOC: dhat::Alloc = dhat::Alloc;
+
+const LOREM_IPSUM_ENCODED: &[u8] = include_bytes!("../../test-assets/lorem_ipsum_encoded.bin");
+
+#[test]
+fn decompress_to_vec_salzweg() {
+    let _profiler = dhat::Profiler::builder().testing().build();
+
+    let start_stats = dhat::HeapStats::get();
+
+    let decompressed = salzweg::decoder::VariableDecoder::decode_to_vec(
+        LOREM_IPSUM_ENCODED,
+        7,
+        12,
+        salzweg::Endianness::LittleEndian,
+        CodeSizeStrategy::Default,
+    )
+    .unwrap();
+
+    let stats = dhat::HeapStats::get();
+
+    // The output starts with room for a few times the length of the input.
+    dhat::assert!(decompressed.capacity() >= 3 * LOREM_IPSUM_ENCODED.len());
+
+    println!("{start_stats:?}");
+    println!("{stats:?}");
+}